@@ -1,13 +1,29 @@
 use crate::{
     gamerules::{
-        combat::{enemy_damage, enemy_turn, mining_laser, scout_attack, Combat},
-        game_functions::{assess_threat, leap_into_system, search_wreckage, system_scan, JumpStep},
-        pilot::{Pilot, PilotStatus},
-        scout::scout_repair,
-        ship::{subsystem_repair, Scout, ShipDamage, SubSystem},
-        threat::{threats_to_fighters, Threats},
+        combat::{
+            advance_combat, auto_resolve, enemy_turn, laser_turn, recall_turn, scout_turn,
+            AutoPolicy, Combat,
+        },
+        events::{resolve_event, PendingEvent},
+        history::{redo, undo, History, Snapshot},
+        outpost::{offers, trade},
+        parts::{Part, Parts},
+        pilot::Pilot,
+        planner::{apply_plan, RepairPlan},
+        roster::Roster,
+        rules::Rules,
+        sector::SectorMap,
+        seed_rng,
+        ship::{subsystem_upgrade, FlightPosition, Scout, Ship, SubSystem},
+        threat::{Fighter, Wreck},
+        turn::{
+            advance, check_action, check_emergency_jump, emergency_jump, stranded_pilots,
+            stranded_scouts, Action, Phase,
+        },
         Leap,
     },
+    replay::{recordable, Playback, Replay, MAX_DELAY, MIN_DELAY},
+    resources::names::{user_lists, NameGenerator},
     tui::interface_core::{select_down, select_up, ui, MenuTabs, Tui},
 };
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
@@ -15,7 +31,7 @@ use ratatui::{
     prelude::*,
     widgets::{ListState, ScrollbarState, TableState},
 };
-use std::io;
+use std::{io, time::Duration};
 
 // define the app
 #[derive(Debug)]
@@ -24,27 +40,35 @@ pub struct App {
     pub exit: bool,
     pub name: String,
     pub leaps_since_incident: u64,
+    pub incident: bool, // a fight was seen through this leap, the next leap restarts the count
     pub fuel: u64,
-    pub parts: u64,
+    pub parts: Parts,
     pub hull_damage: u64,
-    pub hull_upgrade: bool,
+    pub hull_upgrade: u8, // hull upgrade tier, the other subsystems keep theirs in SubSystem
+    #[allow(dead_code)]
     pub hull_destroyed: bool, // TODO: check for critical damage and end game
     pub engine: SubSystem,
     pub mining_laser: SubSystem,
     pub scout_bay: SubSystem,
     pub sick_bay: SubSystem,
     pub sensors: SubSystem,
-    pub scouts: [Scout; 6],
+    pub roster: Roster,
     pub current_leap: Leap,
     pub log: Vec<Leap>,
-    pub pilots: [Pilot; 6],
-    pub pilot_assignment: [usize; 6],
     pub laser_kills: u64,
+    pub rules: Rules,
+    pub boss_defeated: bool,
+    pub sector: SectorMap,
+    pub event: Option<PendingEvent>, // drawn this leap and waiting on a choice
+    pub planner: Option<RepairPlan>, // repair planner overlay, open during upkeep
+    pub history: History,            // undo/redo for decisions made since the last roll
+    pub pursuers: Vec<Fighter>,      // enemies that followed us out of the last fight
+    pub names: NameGenerator,
     pub in_combat: bool,
     pub combat: Option<Combat>,
-    pub bwreckage: bool,
+    pub wreckage: Vec<Wreck>, // salvage record from the last fight
     pub game_text: String,
-    pub jump_step: JumpStep,
+    pub phase: Phase,
     pub hanger_state: TableState,
     pub crew_state: TableState,
     pub editing: bool,
@@ -54,52 +78,88 @@ pub struct App {
     pub combat_scout_state: TableState,
     pub combat_enemy_state: TableState,
     pub subsys_list_state: ListState,
+    pub outpost_state: ListState,
     pub log_scroll_state: ScrollbarState,
     pub log_scroll: usize,
+    pub show_dice: bool,
+    pub show_combat_log: bool,
+    pub auto_policy: AutoPolicy,
+    pub jump_armed: bool, // first <J> shows the emergency jump cost, second one jumps
+    pub recording: Replay, // seed and every command so far, saved as a replay on exit
+    pub playback: Option<Playback>, // set when watching a replay instead of playing
+    pub no_color: bool,   // draw everything in the terminal's default colours
 }
 
 impl Default for App {
+    /// a new campaign on a fresh random seed
     fn default() -> Self {
+        App::new(rand::random(), Rules::default())
+    }
+}
+
+impl App {
+    /// a new campaign with its dice seeded, recording every command for a replay
+    /// names come from the built in lists plus the user's names file, if there is one
+    pub fn new(seed: u64, rules: Rules) -> Self {
+        App::with_names(seed, rules, user_lists())
+    }
+
+    /// a new campaign dealing names from the built in lists plus `lists` (names file form)
+    pub fn with_names(seed: u64, rules: Rules, lists: String) -> Self {
+        seed_rng(seed);
+        let mut names = NameGenerator::with_lists(&lists);
+        // starting crew - six scouts, each with its own pilot
+        let mut roster = Roster::default();
+        for position in FlightPosition::ALL {
+            let scout_id = roster.add_scout(Scout {
+                position,
+                ship: Ship {
+                    name: names.ship_name(),
+                    ..Ship::default()
+                },
+                ..Scout::default()
+            });
+            let pilot_id = roster.add_pilot(Pilot {
+                name: names.pilot_name(),
+                ..Pilot::default()
+            });
+            roster.assign(scout_id, pilot_id);
+        }
+        let mut parts = Parts::even(1);
+        parts.add(Part::Plating, 1);
         Self {
             active_tab: MenuTabs::default(),
             exit: false,
             name: "Lost Ship".to_string(),
             leaps_since_incident: 0,
-            fuel: 6,
-            parts: 6,
+            incident: false,
+            fuel: rules.starting_fuel,
+            parts,
             hull_damage: 0,
-            hull_upgrade: false,
+            hull_upgrade: 0,
             hull_destroyed: false,
             engine: SubSystem::default(),
             mining_laser: SubSystem::default(),
             scout_bay: SubSystem::default(),
             sick_bay: SubSystem::default(),
             sensors: SubSystem::default(),
-            scouts: [
-                Scout::default(),
-                Scout::default(),
-                Scout::default(),
-                Scout::default(),
-                Scout::default(),
-                Scout::default(),
-            ],
+            roster,
             current_leap: Leap::default(),
             log: Vec::new(),
-            pilots: [
-                Pilot::default(),
-                Pilot::default(),
-                Pilot::default(),
-                Pilot::default(),
-                Pilot::default(),
-                Pilot::default(),
-            ],
-            pilot_assignment: [0, 1, 2, 3, 4, 5],
             laser_kills: 0,
+            rules: rules.clone(),
+            boss_defeated: false,
+            pursuers: Vec::new(),
+            sector: SectorMap::default(),
+            event: None,
+            planner: None,
+            history: History::default(),
+            names,
             in_combat: false,
             combat: None,
-            bwreckage: false,
+            wreckage: Vec::new(),
             game_text: String::new(),
-            jump_step: JumpStep::Step1,
+            phase: Phase::default(),
             hanger_state: TableState::default(),
             crew_state: TableState::default(),
             editing: false,
@@ -109,13 +169,44 @@ impl Default for App {
             combat_scout_state: TableState::default(),
             combat_enemy_state: TableState::default(),
             subsys_list_state: ListState::default(),
+            outpost_state: ListState::default(),
             log_scroll_state: ScrollbarState::default(),
             log_scroll: 0,
+            show_dice: false,
+            show_combat_log: false,
+            auto_policy: AutoPolicy::default(),
+            jump_armed: false,
+            recording: Replay::new(seed, rules, lists),
+            playback: None,
+            no_color: false,
         }
     }
-}
 
-impl App {
+    /// plays a recorded campaign back from its seed instead of taking commands from the player
+    pub fn replaying(replay: Replay) -> Self {
+        let mut app = App::with_names(replay.seed, replay.rules.clone(), replay.names.clone());
+        app.playback = Some(Playback::new(replay));
+        app
+    }
+
+    /// rebuilds a saved campaign by running every recorded command, then carries on recording
+    /// from where it left off - fails if the save quits before its last command
+    pub fn resume(replay: Replay) -> Result<Self, String> {
+        let mut app = App::with_names(replay.seed, replay.rules.clone(), replay.names.clone());
+        for (played, code) in replay.commands.iter().enumerate() {
+            app.apply_command(*code);
+            if app.exit {
+                return Err(format!(
+                    "Save quits after command {} of {}.",
+                    played + 1,
+                    replay.commands.len()
+                ));
+            }
+        }
+        app.recording = replay;
+        Ok(app)
+    }
+
     /// runs the application's main loop until the user quits
     pub fn run(&mut self, terminal: &mut Tui) -> io::Result<()> {
         while !self.exit {
@@ -128,19 +219,88 @@ impl App {
     /// render the frame
     fn render_frame(&mut self, frame: &mut Frame) {
         ui(frame, self);
+        if self.no_color {
+            // bold, reversed and the rest stay so selections still show up
+            let area = frame.size();
+            let buffer = frame.buffer_mut();
+            for y in area.top()..area.bottom() {
+                for x in area.left()..area.right() {
+                    buffer
+                        .get_mut(x, y)
+                        .set_fg(Color::Reset)
+                        .set_bg(Color::Reset);
+                }
+            }
+        }
     }
 
     /// update's application state based on user input
     fn handle_events(&mut self) -> io::Result<()> {
+        if self.playback.is_some() {
+            return self.handle_playback_events();
+        }
         match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event);
+            Event::Key(key_event)
+                if key_event.kind == KeyEventKind::Press && recordable(&key_event.code) =>
+            {
+                self.recording.commands.push(key_event.code);
+                self.apply_command(key_event.code);
+                if self.exit {
+                    self.recording.commands.pop(); // quitting isn't part of the game
+                }
             }
             _ => {}
         };
         Ok(())
     }
 
+    /// runs one command through the game - a key from the player or from a replay
+    pub fn apply_command(&mut self, code: KeyCode) {
+        let before = Snapshot::take(self);
+        let decision = self.is_decision(code);
+        let stepping = self.is_history_key(code);
+        self.handle_key_event(KeyEvent::from(code));
+        advance_combat(self);
+        if !stepping {
+            self.history.settle(before, &Snapshot::take(self), decision);
+        }
+    }
+
+    /// playback controls, and the next recorded command whenever the delay runs out
+    /// <Space> plays or pauses, <Right> steps one command, <+>/<-> change speed, <Q> quits
+    fn handle_playback_events(&mut self) -> io::Result<()> {
+        let Some(playback) = self.playback.as_mut() else {
+            return Ok(());
+        };
+        let (playing, delay) = (playback.playing, playback.delay);
+        if !playing || event::poll(Duration::from_millis(delay))? {
+            if let Event::Key(key_event) = event::read()? {
+                if key_event.kind == KeyEventKind::Press {
+                    match key_event.code {
+                        KeyCode::Char('q') => self.exit(),
+                        KeyCode::Char(' ') => playback.playing = !playback.playing,
+                        KeyCode::Right | KeyCode::Char('.') => {
+                            playback.playing = false;
+                            if let Some(code) = playback.next_command() {
+                                self.apply_command(code);
+                            }
+                        }
+                        KeyCode::Char('+') => {
+                            playback.delay = (playback.delay / 2).max(MIN_DELAY);
+                        }
+                        KeyCode::Char('-') => {
+                            playback.delay = (playback.delay * 2).min(MAX_DELAY);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        } else if let Some(code) = playback.next_command() {
+            self.apply_command(code);
+        }
+        Ok(())
+    }
+
     /// handle key events
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         if self.editing {
@@ -158,7 +318,12 @@ impl App {
                 }
                 _ => {}
             }
+        } else if self.planner.is_some() {
+            planner_key_press(self, key_event.code);
         } else {
+            if key_event.code != KeyCode::Char('j') {
+                self.jump_armed = false; // anything else cancels a pending jump
+            }
             match key_event.code {
                 KeyCode::Char('q') => self.exit(),
                 KeyCode::Char('1') => self.active_tab = MenuTabs::Status,
@@ -166,16 +331,37 @@ impl App {
                 KeyCode::Char('3') => self.active_tab = MenuTabs::Hangar,
                 KeyCode::Char('4') => self.active_tab = MenuTabs::Crew,
                 KeyCode::Char('5') => self.active_tab = MenuTabs::Combat,
-                KeyCode::Char('6') => self.active_tab = MenuTabs::About,
-                KeyCode::Char('7') => self.active_tab = MenuTabs::Help,
+                KeyCode::Char('6') => self.active_tab = MenuTabs::Map,
+                KeyCode::Char('7') => self.active_tab = MenuTabs::Outpost,
+                KeyCode::Char('8') => self.active_tab = MenuTabs::About,
+                KeyCode::Char('9') => self.active_tab = MenuTabs::Help,
                 KeyCode::Char('n') => n_key_press(self),
                 KeyCode::Char('e') => edit_press(self),
                 KeyCode::Char('w') => w_key_press(self),
                 KeyCode::Char('s') => s_key_press(self),
                 KeyCode::Char('a') => a_key_press(self),
                 KeyCode::Char('m') => m_key_press(self),
+                KeyCode::Char('c') => c_key_press(self),
+                KeyCode::Char('j') => j_key_press(self),
                 KeyCode::Char('r') => r_key_press(self),
                 KeyCode::Char('u') => u_key_press(self),
+                KeyCode::Char('d') => self.show_dice = !self.show_dice,
+                KeyCode::Char('l') => self.show_combat_log = !self.show_combat_log,
+                KeyCode::Char('x') => x_key_press(self),
+                KeyCode::Char('z') => {
+                    self.game_text = match undo(self) {
+                        Ok(text) | Err(text) => text,
+                    };
+                }
+                KeyCode::Char('y') => {
+                    self.game_text = match redo(self) {
+                        Ok(text) | Err(text) => text,
+                    };
+                }
+                KeyCode::Char('p') if self.active_tab == MenuTabs::Combat => {
+                    self.auto_policy = self.auto_policy.next();
+                }
+                KeyCode::Enter => confirm_press(self),
                 KeyCode::Up => up_press(self),
                 KeyCode::Down => down_press(self),
                 KeyCode::Left => left_press(self),
@@ -185,6 +371,26 @@ impl App {
         }
     }
 
+    /// keys for choices that don't roll any dice, and so can be undone
+    /// renames, formation changes, upgrades, the repair plan and outpost trades
+    fn is_decision(&self, code: KeyCode) -> bool {
+        if self.editing || self.planner.is_some() {
+            return code == KeyCode::Enter;
+        }
+        match code {
+            KeyCode::Char('w') | KeyCode::Char('s') | KeyCode::Char('u') => true,
+            KeyCode::Enter => self.active_tab == MenuTabs::Outpost,
+            _ => false,
+        }
+    }
+
+    /// undo and redo keys, which step through the history rather than adding to it
+    fn is_history_key(&self, code: KeyCode) -> bool {
+        !self.editing
+            && self.planner.is_none()
+            && matches!(code, KeyCode::Char('z') | KeyCode::Char('y'))
+    }
+
     /// app methods
     fn exit(&mut self) {
         self.exit = true;
@@ -196,39 +402,32 @@ impl App {
 /// enables edit mode and clears previous string just in case
 fn edit_press(app: &mut App) {
     match app.active_tab {
-        MenuTabs::Hangar => {
-            if app.hanger_state.selected().is_some() {
-                app.editing = true;
-                app.edit_string = String::new();
-                app.edit_target = Some(app.hanger_state.selected().unwrap());
-            }
+        MenuTabs::Hangar if app.hanger_state.selected().is_some() => {
+            app.editing = true;
+            app.edit_string = String::new();
+            app.edit_target = app.hanger_state.selected();
         }
-        MenuTabs::Crew => {
-            if app.crew_state.selected().is_some() {
-                app.editing = true;
-                app.edit_string = String::new();
-                app.edit_target = Some(app.crew_state.selected().unwrap());
-            }
+        MenuTabs::Crew if app.crew_state.selected().is_some() => {
+            app.editing = true;
+            app.edit_string = String::new();
+            app.edit_target = app.crew_state.selected();
         }
         _ => {}
     }
 }
 
 fn enter_press(app: &mut App) {
-    match app.active_tab {
-        MenuTabs::Hangar => {
-            if app.edit_target.is_some() {
-                app.scouts[app.edit_target.unwrap()]
-                    .ship
-                    .name
-                    .clone_from(&app.edit_string);
+    match (app.active_tab, app.edit_target) {
+        (MenuTabs::Hangar, Some(target)) => {
+            app.names.register(&app.edit_string);
+            if let Some(scout) = app.roster.scouts.get_mut(target) {
+                scout.ship.name.clone_from(&app.edit_string);
             }
         }
-        MenuTabs::Crew => {
-            if app.edit_target.is_some() {
-                app.pilots[app.edit_target.unwrap()]
-                    .name
-                    .clone_from(&app.edit_string);
+        (MenuTabs::Crew, Some(target)) => {
+            app.names.register(&app.edit_string);
+            if let Some(pilot) = app.roster.pilots.get_mut(target) {
+                pilot.name.clone_from(&app.edit_string);
             }
         }
         _ => {}
@@ -236,6 +435,35 @@ fn enter_press(app: &mut App) {
     app.editing = false;
 }
 
+/// logic for enter presses outside of editing
+/// resolves the selected choice of a pending event on the Status tab
+/// makes the selected trade on the Outpost tab (only works in upkeep phase)
+fn confirm_press(app: &mut App) {
+    match app.active_tab {
+        MenuTabs::Status if app.event.is_some() => {
+            app.game_text = match resolve_event(app) {
+                Ok(text) | Err(text) => text,
+            };
+        }
+        MenuTabs::Outpost => {
+            if !allowed(app, Action::Trade) {
+                return;
+            }
+            if let Some(selected) = app.outpost_state.selected() {
+                app.game_text = match trade(app, selected) {
+                    Ok(text) | Err(text) => text,
+                };
+                // the list shrinks as stock runs out
+                let remaining = offers(app).len();
+                if selected >= remaining {
+                    app.outpost_state.select(remaining.checked_sub(1));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 /// logic for up arrow key presses
 /// adjusts table selection up with wrapping on Hangar/Crew/Combat tabs
 fn up_press(app: &mut App) {
@@ -244,30 +472,46 @@ fn up_press(app: &mut App) {
             let _ = app.log_scroll.saturating_add(1); // TODO: maybe use your select_up function
             app.log_scroll_state = app.log_scroll_state.position(app.log_scroll);
         }
-        MenuTabs::Hangar => app
-            .hanger_state
-            .select(select_up(app.hanger_state.selected(), app.scouts.len())),
-        MenuTabs::Crew => app
-            .crew_state
-            .select(select_up(app.crew_state.selected(), app.pilots.len())),
+        MenuTabs::Hangar => app.hanger_state.select(select_up(
+            app.hanger_state.selected(),
+            app.roster.scouts.len(),
+        )),
+        MenuTabs::Crew => app.crew_state.select(select_up(
+            app.crew_state.selected(),
+            app.roster.pilots.len(),
+        )),
         MenuTabs::Combat => {
-            if app.combat.is_some() {
+            if let Some(combat) = &app.combat {
                 if app.combat_select {
                     app.combat_scout_state.select(select_up(
                         app.combat_scout_state.selected(),
-                        app.scouts.len(),
+                        combat.scout_formation.len(),
                     ));
                 } else {
                     app.combat_enemy_state.select(select_up(
                         app.combat_enemy_state.selected(),
-                        app.combat.as_ref().unwrap().enemy_formation.len(),
+                        combat.enemy_formation.len(),
                     ));
                 }
             }
         }
         MenuTabs::Status => {
-            app.subsys_list_state
-                .select(select_up(app.subsys_list_state.selected(), 6));
+            if let Some(event) = app.event.as_mut() {
+                let choices = event.card.choices.len();
+                event.selected = select_up(Some(event.selected), choices).unwrap_or(0);
+            } else {
+                app.subsys_list_state
+                    .select(select_up(app.subsys_list_state.selected(), 6));
+            }
+        }
+        MenuTabs::Map => {
+            let choices = app.sector.choices.len();
+            app.sector.selected = select_up(Some(app.sector.selected), choices).unwrap_or(0);
+        }
+        MenuTabs::Outpost => {
+            let choices = offers(app).len();
+            app.outpost_state
+                .select(select_up(app.outpost_state.selected(), choices));
         }
         _ => {}
     }
@@ -281,30 +525,46 @@ fn down_press(app: &mut App) {
             let _ = app.log_scroll.saturating_sub(1);
             app.log_scroll_state = app.log_scroll_state.position(app.log_scroll);
         }
-        MenuTabs::Hangar => app
-            .hanger_state
-            .select(select_down(app.hanger_state.selected(), app.scouts.len())),
-        MenuTabs::Crew => app
-            .crew_state
-            .select(select_down(app.crew_state.selected(), app.pilots.len())),
+        MenuTabs::Hangar => app.hanger_state.select(select_down(
+            app.hanger_state.selected(),
+            app.roster.scouts.len(),
+        )),
+        MenuTabs::Crew => app.crew_state.select(select_down(
+            app.crew_state.selected(),
+            app.roster.pilots.len(),
+        )),
         MenuTabs::Combat => {
-            if app.combat.is_some() {
+            if let Some(combat) = &app.combat {
                 if app.combat_select {
                     app.combat_scout_state.select(select_down(
                         app.combat_scout_state.selected(),
-                        app.scouts.len(),
+                        combat.scout_formation.len(),
                     ));
                 } else {
                     app.combat_enemy_state.select(select_down(
                         app.combat_enemy_state.selected(),
-                        app.combat.as_ref().unwrap().enemy_formation.len(),
+                        combat.enemy_formation.len(),
                     ));
                 }
             }
         }
         MenuTabs::Status => {
-            app.subsys_list_state
-                .select(select_down(app.subsys_list_state.selected(), 6));
+            if let Some(event) = app.event.as_mut() {
+                let choices = event.card.choices.len();
+                event.selected = select_down(Some(event.selected), choices).unwrap_or(0);
+            } else {
+                app.subsys_list_state
+                    .select(select_down(app.subsys_list_state.selected(), 6));
+            }
+        }
+        MenuTabs::Map => {
+            let choices = app.sector.choices.len();
+            app.sector.selected = select_down(Some(app.sector.selected), choices).unwrap_or(0);
+        }
+        MenuTabs::Outpost => {
+            let choices = offers(app).len();
+            app.outpost_state
+                .select(select_down(app.outpost_state.selected(), choices));
         }
         _ => {}
     }
@@ -312,70 +572,52 @@ fn down_press(app: &mut App) {
 
 /// logic for left arrow presses
 fn left_press(app: &mut App) {
-    match app.active_tab {
-        MenuTabs::Combat => app.combat_select = true,
-        _ => {}
+    if app.active_tab == MenuTabs::Combat {
+        app.combat_select = true;
     }
 }
 
 /// logic for right arrow presses
 fn right_press(app: &mut App) {
-    match app.active_tab {
-        MenuTabs::Combat => app.combat_select = false,
-        _ => {}
-    }
-}
-
-/// shifts position of selected element up (swaps with n-1 element with wrapping)
-fn shift_up<T: Clone>(v: &mut [T], pos: usize) {
-    let max_pos = v.len() - 1;
-    let b = if pos == 0 {
-        v[max_pos].clone()
-    } else {
-        v[pos - 1].clone()
-    };
-    let a = v[pos].clone();
-    v[pos] = b;
-    if pos == 0 {
-        v[max_pos] = a;
-    } else {
-        v[pos - 1] = a;
+    if app.active_tab == MenuTabs::Combat {
+        app.combat_select = false;
     }
 }
 
-/// shifts position of selected element down (swaps with n+1 element with wrapping)
-fn shift_down<T: Clone>(v: &mut [T], pos: usize) {
-    let max_pos = v.len() - 1;
-    let b = if pos == max_pos {
-        v[0].clone()
-    } else {
-        v[pos + 1].clone()
-    };
-    let a = v[pos].clone();
-    v[pos] = b;
-    if pos == max_pos {
-        v[0] = a;
-    } else {
-        v[pos + 1] = a;
+/// checks an action against the turn phase, showing the reason when it's rejected
+fn allowed(app: &mut App, action: Action) -> bool {
+    match check_action(app, action) {
+        Ok(()) => true,
+        Err(reason) => {
+            if let Some(combat) = app.combat.as_mut().filter(|_| app.in_combat) {
+                combat.combat_text.clone_from(&reason);
+            }
+            app.game_text = reason;
+            false
+        }
     }
 }
 
 /// logic for w key presses
 fn w_key_press(app: &mut App) {
-    match app.active_tab {
-        MenuTabs::Crew => {
-            if app.crew_state.selected().is_some() {
-                shift_up(&mut app.pilots, app.crew_state.selected().unwrap());
-                app.crew_state
-                    .select(select_up(app.crew_state.selected(), app.pilots.len()));
+    match (
+        app.active_tab,
+        app.crew_state.selected(),
+        app.hanger_state.selected(),
+    ) {
+        (MenuTabs::Crew, Some(_), _) if !allowed(app, Action::Reposition) => {}
+        (MenuTabs::Crew, Some(selected), _) => {
+            // pilots trade places in the crew list and swap the scouts they fly
+            let target = select_up(Some(selected), app.roster.pilots.len());
+            if let Some(target) = target {
+                app.roster.swap_pilots(selected, target);
             }
+            app.crew_state.select(target);
         }
-        MenuTabs::Hangar => {
-            if app.hanger_state.selected().is_some() {
-                shift_up(&mut app.scouts, app.hanger_state.selected().unwrap());
-                shift_up(&mut app.pilots, app.hanger_state.selected().unwrap());
-                app.hanger_state
-                    .select(select_up(app.hanger_state.selected(), app.scouts.len()));
+        // flight positions are locked once the fighting starts
+        (MenuTabs::Hangar, _, Some(selected)) if allowed(app, Action::Reposition) => {
+            if let Some(moved) = app.roster.move_scout(selected, true) {
+                app.hanger_state.select(Some(moved));
             }
         }
         _ => {}
@@ -384,20 +626,24 @@ fn w_key_press(app: &mut App) {
 
 /// logic for s key presses
 fn s_key_press(app: &mut App) {
-    match app.active_tab {
-        MenuTabs::Crew => {
-            if app.crew_state.selected().is_some() {
-                shift_down(&mut app.pilots, app.crew_state.selected().unwrap());
-                app.crew_state
-                    .select(select_down(app.crew_state.selected(), app.pilots.len()));
+    match (
+        app.active_tab,
+        app.crew_state.selected(),
+        app.hanger_state.selected(),
+    ) {
+        (MenuTabs::Crew, Some(_), _) if !allowed(app, Action::Reposition) => {}
+        (MenuTabs::Crew, Some(selected), _) => {
+            // pilots trade places in the crew list and swap the scouts they fly
+            let target = select_down(Some(selected), app.roster.pilots.len());
+            if let Some(target) = target {
+                app.roster.swap_pilots(selected, target);
             }
+            app.crew_state.select(target);
         }
-        MenuTabs::Hangar => {
-            if app.hanger_state.selected().is_some() {
-                shift_down(&mut app.scouts, app.hanger_state.selected().unwrap());
-                shift_down(&mut app.pilots, app.hanger_state.selected().unwrap());
-                app.hanger_state
-                    .select(select_down(app.hanger_state.selected(), app.scouts.len()));
+        // flight positions are locked once the fighting starts
+        (MenuTabs::Hangar, _, Some(selected)) if allowed(app, Action::Reposition) => {
+            if let Some(moved) = app.roster.move_scout(selected, false) {
+                app.hanger_state.select(Some(moved));
             }
         }
         _ => {}
@@ -408,279 +654,190 @@ fn s_key_press(app: &mut App) {
 /// if in combat AND scout turn AND selected valid scout AND enemy, roll for damage
 /// also handles upgrading rank if pilot scores a kill
 fn a_key_press(app: &mut App) {
-    if app.combat.is_some()
-        && app.combat.as_ref().unwrap().scout_half
-        && app.combat_scout_state.selected().is_some()
-        && app.combat_enemy_state.selected().is_some()
-    {
-        // make sure valid ships are selected (not destroyed, etc.)
-        let mut combat = app.combat.clone().unwrap();
-        let scout_pos = app.combat_scout_state.selected().unwrap();
-        let scout = app.scouts[scout_pos].clone();
-        let turn_ok = combat.scout_turns[scout_pos];
-        let enemy_pos = app.combat_enemy_state.selected().unwrap();
-        let enemy = combat.enemy_stats[enemy_pos].clone();
-        let ship_ok = matches!(scout.ship.damage, ShipDamage::Normal | ShipDamage::Half);
-        let pilot_ok = matches!(
-            scout.pilot.status,
-            PilotStatus::Normal | PilotStatus::Injured
-        );
-        let target_ok = enemy.fuel > 0 && enemy.hp > 0; // bool literal?
-
-        if ship_ok && pilot_ok && target_ok && !turn_ok {
-            let damage = scout_attack(&scout);
-            // update combat log
-            app.current_leap.damage[enemy_pos] += damage;
-            // apply damage
-            combat.enemy_stats[enemy_pos].hp = enemy_damage(damage, enemy.hp);
-            // check for kill and mark if appropriate
-            // TODO: clean this up
-            if combat.enemy_stats[enemy_pos].hp == 0 {
-                app.scouts[scout_pos].pilot.mark_kill(&enemy.model);
-                app.scouts[scout_pos].pilot.rank_up();
-                app.pilots[scout_pos].mark_kill(&enemy.model);
-                app.pilots[scout_pos].rank_up();
-                combat.scout_formation[scout_pos]
-                    .pilot
-                    .mark_kill(&enemy.model);
-                combat.scout_formation[scout_pos].pilot.rank_up();
-                // NOTE: This is because pilot information and order is copied into scout struct at
-                // certain points (like when drawing combat tab).  Probably shouldn't do that, come
-                // back to this when you've found a better way.
-            }
-            combat.scout_turns[scout_pos] = true;
-            combat.combat_text = format!(
-                "{} deals {} damage to {}",
-                scout.pilot.name, damage, enemy.model
-            );
-        } else {
-            combat.combat_text = "Make sure a valid scout and target are selected.".to_string();
+    if !allowed(app, Action::Attack) {
+        return;
+    }
+    if let (Some(mut combat), Some(scout_pos), Some(enemy_pos)) = (
+        app.combat.clone(),
+        app.combat_scout_state.selected(),
+        app.combat_enemy_state.selected(),
+    ) {
+        if combat.scout_half {
+            combat.combat_text = match scout_turn(app, &mut combat, scout_pos, enemy_pos) {
+                Ok(text) | Err(text) => text,
+            };
+            app.combat = Some(combat); // rewrap and assign to app state
         }
-
-        app.combat = Some(combat); // rewrap and assign to app state
     }
 }
 
 /// logic for m key press
 /// if in combat past first round, triggers mining laser attack on selected enemy
 fn m_key_press(app: &mut App) {
-    if app.combat.is_some()
-        && app.combat.as_ref().unwrap().scout_half
-        && app.combat_enemy_state.selected().is_some()
-        && !app.combat.as_ref().unwrap().laser_fired
+    if !allowed(app, Action::Laser) {
+        return;
+    }
+    if let (Some(mut combat), Some(enemy_pos)) =
+        (app.combat.clone(), app.combat_enemy_state.selected())
     {
-        let mut combat = app.combat.clone().unwrap();
-        let enemy_pos = app.combat_enemy_state.selected().unwrap();
-        let enemy = combat.enemy_stats[enemy_pos].clone();
-        let target_ok = enemy.fuel > 0 && enemy.hp > 0;
-        if target_ok && combat.rounds > 1 {
-            let damage = mining_laser(app.mining_laser.upgrade);
-            // update leap log
-            app.current_leap.damage[enemy_pos] += damage;
-            // apply damage
-            combat.enemy_stats[enemy_pos].hp = enemy_damage(damage, enemy.hp);
-            if combat.enemy_stats[enemy_pos].hp == 0 {
-                match combat.enemy_stats[enemy_pos].model {
-                    Threats::Mk1 => app.laser_kills += 1,
-                    Threats::Mk2 => app.laser_kills += 2,
-                    Threats::Mk3 => app.laser_kills += 3,
-                    Threats::None => {}
-                }
-                if app.laser_kills >= 5 {
-                    app.mining_laser.upgrade = true;
-                }
+        if combat.scout_half && !combat.laser_fired {
+            combat.combat_text = match laser_turn(app, &mut combat, enemy_pos) {
+                Ok(text) | Err(text) => text,
+            };
+            app.combat = Some(combat);
+        }
+    }
+}
+
+/// logic for c key press
+/// recalls the selected scout to the scout bay for the rest of the fight
+fn c_key_press(app: &mut App) {
+    if app.active_tab != MenuTabs::Combat || !allowed(app, Action::Recall) {
+        return;
+    }
+    if let (Some(mut combat), Some(scout_pos)) =
+        (app.combat.clone(), app.combat_scout_state.selected())
+    {
+        combat.combat_text = match recall_turn(app, &mut combat, scout_pos) {
+            Ok(text) | Err(text) => text,
+        };
+        app.combat = Some(combat);
+    }
+}
+
+/// logic for j key press
+/// first press shows what an emergency jump will cost, a second press makes the jump
+fn j_key_press(app: &mut App) {
+    if app.active_tab != MenuTabs::Combat || !allowed(app, Action::EmergencyJump) {
+        return;
+    }
+    let extra = match check_emergency_jump(app) {
+        Ok(extra) => extra,
+        Err(reason) => {
+            if let Some(combat) = app.combat.as_mut() {
+                combat.combat_text = reason;
             }
-            combat.laser_fired = true;
-            combat.combat_text = format!("Mining laser deals {} damage to {}", damage, enemy.model);
-        } else {
-            combat.combat_text = "Mining laser available starting in round 2.  Make sure a valid target is selected.".to_string();
+            return;
+        }
+    };
+    if app.jump_armed {
+        app.jump_armed = false;
+        if let Err(reason) = emergency_jump(app) {
+            app.game_text = reason;
         }
+        app.active_tab = MenuTabs::Status;
+        return;
+    }
+    app.jump_armed = true;
+    if let Some(mut combat) = app.combat.clone() {
+        let stranded = stranded_scouts(app)
+            .iter()
+            .filter_map(|id| app.roster.scout(*id))
+            .map(|x| x.ship.name.clone())
+            .collect::<Vec<String>>();
+        let left = if stranded.is_empty() {
+            "no scouts".to_string()
+        } else {
+            stranded.join(", ")
+        };
+        let pilots = stranded_pilots(app);
+        let lost = if pilots.is_empty() {
+            String::new()
+        } else {
+            format!("  {} will be lost with them.", pilots.join(", "))
+        };
+        combat.combat_text = format!(
+            "Emergency jump burns {} fuel ({extra} extra), forfeits the salvage and leaves behind {left}.{lost}  Press <J> again to jump.",
+            extra + 1
+        );
         app.combat = Some(combat);
     }
 }
 
+/// logic for x key press
+/// auto-resolves the current fight with the selected policy, then opens the combat log
+fn x_key_press(app: &mut App) {
+    if app.active_tab == MenuTabs::Combat
+        && allowed(app, Action::AutoResolve)
+        && app.in_combat
+        && app.combat.is_some()
+    {
+        let summary = auto_resolve(app, app.auto_policy, true);
+        if let Some(combat) = app.combat.as_mut() {
+            combat.combat_text = summary;
+        }
+        app.show_combat_log = true;
+    }
+}
+
 /// logic for r key presses
-/// results depend on tab and selection - only available in step 6
-/// repairs scout damage, consuming parts (50% damage is repaired for free!)
-/// repairs sub systems when selected
+/// only active on Hangar and Status tabs in the upkeep phase, opens the repair planner
 fn r_key_press(app: &mut App) {
-    if app.jump_step == JumpStep::Step6 {
-        match app.active_tab {
-            MenuTabs::Hangar => {
-                if app.hanger_state.selected().is_some() {
-                    scout_repair(app, app.hanger_state.selected().unwrap());
-                }
-                // TODO: notification to select a damaged scout
-            }
-            MenuTabs::Status => {
-                if app.subsys_list_state.selected().is_some() {
-                    subsystem_repair(app, app.subsys_list_state.selected().unwrap());
-                }
-            }
-            _ => {}
+    if matches!(app.active_tab, MenuTabs::Hangar | MenuTabs::Status) && allowed(app, Action::Repair)
+    {
+        app.planner = Some(RepairPlan::new(app));
+    }
+}
+
+/// keys while the repair planner is open
+/// <Space> queues the selected job, <G> suggests a plan, <Enter> applies it all, <Esc> closes
+fn planner_key_press(app: &mut App, code: KeyCode) {
+    let Some(mut plan) = app.planner.take() else {
+        return;
+    };
+    match code {
+        KeyCode::Esc => return,
+        KeyCode::Up => {
+            plan.selected = select_up(Some(plan.selected), plan.entries.len()).unwrap_or(0);
+        }
+        KeyCode::Down => {
+            plan.selected = select_down(Some(plan.selected), plan.entries.len()).unwrap_or(0);
         }
+        KeyCode::Char(' ') => plan.toggle(),
+        KeyCode::Char('g') => plan.suggest(&app.parts),
+        KeyCode::Enter => match apply_plan(app, &plan) {
+            Ok(text) => {
+                app.game_text = text;
+                return;
+            }
+            Err(text) => app.game_text = text,
+        },
+        KeyCode::Char('q') => app.exit(),
+        _ => {}
     }
+    app.planner = Some(plan);
 }
 
 /// logic for u key presses
-/// only active on Status tab, upgrades subsystem if not already upgraded and enough parts are
-/// available (only works in repair phase)
+/// only active on Status tab, buys the selected subsystem's next upgrade tier if enough of its
+/// parts are available (only works in repair phase)
 fn u_key_press(app: &mut App) {
-    if app.jump_step == JumpStep::Step6 {
-        match app.active_tab {
-            MenuTabs::Status => {
-                if app.hanger_state.selected().is_some() && app.parts >= 4 {
-                    let ss = app.hanger_state.selected().unwrap();
-                    if ss == 0 {
-                        app.hull_upgrade = true;
-                    } else if ss == 1 {
-                        app.engine.upgrade = true;
-                    } else if ss == 2 {
-                        app.mining_laser.upgrade = true;
-                    } else if ss == 3 {
-                        app.scout_bay.upgrade = true;
-                    } else if ss == 4 {
-                        app.sick_bay.upgrade = true;
-                    } else {
-                        app.sensors.upgrade = true;
-                    }
-                    app.parts -= 4;
-                }
-            }
-            _ => {}
+    if app.active_tab == MenuTabs::Status && allowed(app, Action::Upgrade) {
+        if let Some(ss) = app.subsys_list_state.selected() {
+            app.game_text = match subsystem_upgrade(app, ss) {
+                Ok(text) | Err(text) => text,
+            };
         }
     }
 }
 
 /// logic for n key presses
-/// only active on Status and Combat tabs, advances one step at a time and waits for combat to resolve
+/// only active on Status and Combat tabs, advances one phase at a time and waits for combat to resolve
 /// on Combat tab, used to advance through enemy turn
 fn n_key_press(app: &mut App) {
     match app.active_tab {
         MenuTabs::Status => {
-            match app.jump_step {
-                JumpStep::Step1 => {
-                    app.current_leap = Leap::default(); // reset current leap log
-                    app.current_leap.number = app.leaps_since_incident + 1;
-                    app.game_text = "Jumping into a new system ...".to_string();
-                    leap_into_system(app);
-                    app.jump_step = JumpStep::Step2;
-                }
-                JumpStep::Step2 => {
-                    app.game_text = "Assessing threats ...".to_string();
-                    let scout_vec = Vec::from(app.scouts.clone());
-                    let enemy_vec = match assess_threat(app) {
-                        Some(ev) => {
-                            app.game_text += "Enemy ships are preparing to engage!";
-                            app.in_combat = true;
-                            app.bwreckage = true;
-                            ev
-                        }
-                        None => {
-                            app.game_text += "Sector clear.  Whew!";
-                            app.in_combat = false;
-                            app.bwreckage = false;
-                            vec![Threats::None]
-                        }
-                    };
-                    // update log with threat info
-                    app.current_leap.threats = enemy_vec.clone();
-                    app.current_leap.damage = vec![0; enemy_vec.len()];
-                    app.combat = Some(Combat {
-                        rounds: 1,
-                        scout_turns: vec![false; scout_vec.len()],
-                        scout_formation: scout_vec,
-                        enemy_turns: vec![false; enemy_vec.len()],
-                        enemy_stats: threats_to_fighters(&enemy_vec),
-                        enemy_formation: enemy_vec,
-                        scout_half: true,
-                        laser_fired: false,
-                        combat_text: "Enemy ships sighted!  Prepare to engage!".to_string(),
-                    });
-                    app.jump_step = JumpStep::Step3;
-                }
-                JumpStep::Step3 => {
-                    /*
-                    app.combat = Some(Combat {
-                        rounds: 1,
-                        scout_formation: app.scouts.to_vec(),
-                        enemy_formation: vec![Threats::Mk1, Threats::Mk2],
-                        enemy_stats: threats_to_fighters(&[Threats::Mk1, Threats::Mk2]),
-                        scout_turns: vec![false; 6],
-                        enemy_turns: vec![false, false],
-                        scout_half: true,
-                        laser_fired: false,
-                        combat_text: "Enemy ships sighted!  Prepare to engage!".to_string(),
-                    });
-                    app.in_combat = true;
-                    */
-                    if !app.in_combat {
-                        app.jump_step = JumpStep::Step4;
-                    }
-                }
-                JumpStep::Step4 => {
-                    // TODO: error proof
-                    if app.bwreckage {
-                        let parts = search_wreckage(&app.combat.clone().unwrap().enemy_formation);
-                        app.parts += parts;
-                        app.current_leap.parts_found = parts; // update log
-                        app.game_text =
-                            format!("You search through the wreckage and recover {parts} parts.");
-                    } else {
-                        app.game_text = "No wreckage to salvage.".to_string();
-                    }
-                    app.jump_step = JumpStep::Step5;
-                }
-                JumpStep::Step5 => {
-                    let (fuel, scan_result) = system_scan(app.leaps_since_incident);
-                    app.fuel += fuel;
-                    app.current_leap.fuel_found = fuel; // update log
-                    app.game_text = format!(
-                        "Scanning system... {scan_result} - gathered {fuel} fuel.  Make repairs and upkeep."
-                    );
-                    // TODO: handle anomoly and home scans
-                    app.jump_step = JumpStep::Step6;
-                }
-                JumpStep::Step6 => {
-                    // scouts at 50% are repaired for free
-                    // inoperable scouts can be repaired for 1 part
-                    // each point of hull damage can be repaired for 1 part
-                    // a scout can be scrapped for +4 parts
-                    // repairing any system requires 2 parts
-                    // upgrading a system costs 4 parts
-                    // building a new scout costs 6 parts
-                    // after every 5th leap you get a free upgrade
-                    // injured pilots heal according to sick bay - do this last
-                    // inoperable sick bay means newly injured pilots die
-                    // start training up new pilots
-                    app.game_text = "Heal and train new pilots.".to_string();
-                    app.log.push(app.current_leap.clone());
-                    app.jump_step = JumpStep::Step7;
-                }
-                JumpStep::Step7 => {
-                    app.game_text = "Can this step be removed?  I thought it would make sense to keep a while ago.".to_string();
-                    app.jump_step = JumpStep::Step1;
-                }
+            if let Err(reason) = advance(app) {
+                app.game_text = reason;
             }
         }
         MenuTabs::Combat => {
-            if app.combat.is_some() && !app.combat.as_ref().unwrap().scout_half {
-                let mut combat = app.combat.clone().unwrap();
+            if !allowed(app, Action::EnemyTurn) {
+                return;
+            }
+            if let Some(mut combat) = app.combat.clone().filter(|x| !x.scout_half) {
                 enemy_turn(&mut combat, app);
-                app.combat = Some(combat);
-            } else if app.combat.is_some() && app.combat.as_ref().unwrap().scout_half {
-                // TODO: debug only, delete this branch
-                let mut combat = app.combat.clone().unwrap();
-                combat.combat_text = format!(
-                    "{:?} {:?} {:?} {:?} {:?} {:?} {:?}",
-                    combat.scout_turns[0],
-                    combat.scout_turns[1],
-                    combat.scout_turns[2],
-                    combat.scout_turns[3],
-                    combat.scout_turns[4],
-                    combat.scout_turns[5],
-                    combat.laser_fired,
-                );
+                combat.log.push(combat.combat_text.clone());
                 app.combat = Some(combat);
             }
         }
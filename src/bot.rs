@@ -0,0 +1,499 @@
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    app::App,
+    gamerules::{
+        combat::{
+            advance_combat, auto_resolve, enemy_turn, laser_turn, recall_cost, recall_turn,
+            scout_turn, AutoPolicy, Combat,
+        },
+        events::resolve_event,
+        odds::forecast_detail,
+        outpost::{offers, trade},
+        parts::Part,
+        pilot::PilotStatus,
+        planner::{apply_plan, RepairPlan},
+        ship::{max_hull, subsystem_upgrade, tier_cost, upgrade_tier, ShipDamage, SUBSYSTEM_NAMES},
+        turn::{
+            advance, check_action, check_emergency_jump, emergency_jump, stranded_pilots, Action,
+            Phase,
+        },
+    },
+    sim::game_over,
+};
+
+/// bumped whenever a command or the state changes shape
+pub const PROTOCOL_VERSION: u64 = 1;
+
+/// a command from a bot - one JSON object per line, named by its "cmd" field
+/// scouts and targets are positions in the fight's formations, jobs and offers are indices
+/// into the lists under `legal_actions`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    State,
+    Log,
+    Quit,
+    Advance,
+    Leap { destination: Option<usize> },
+    Attack { scout: usize, target: usize },
+    Laser { target: usize },
+    EnemyTurn,
+    AutoResolve { policy: Option<String> },
+    Recall { scout: usize },
+    EmergencyJump,
+    Event { choice: usize },
+    Repair { jobs: Option<Vec<usize>> },
+    Upgrade { subsystem: usize },
+    Trade { offer: usize },
+}
+
+/// why a command was turned down
+/// `bad_request` the line wasn't a command, `not_allowed` the phase rules forbid it,
+/// `invalid` the rules refused it as asked, `game_over` the campaign can't go on
+#[derive(Debug, Serialize)]
+struct Failure {
+    code: &'static str,
+    message: String,
+}
+
+impl Failure {
+    fn bad_request(message: String) -> Self {
+        Failure {
+            code: "bad_request",
+            message,
+        }
+    }
+
+    fn not_allowed(message: String) -> Self {
+        Failure {
+            code: "not_allowed",
+            message,
+        }
+    }
+
+    fn invalid(message: String) -> Self {
+        Failure {
+            code: "invalid",
+            message,
+        }
+    }
+}
+
+/// checks an action against the phase rules
+fn allow(app: &App, action: Action) -> Result<(), Failure> {
+    check_action(app, action).map_err(Failure::not_allowed)
+}
+
+/// the fight in progress, to hand to the combat rules
+fn fight(app: &App) -> Result<Combat, Failure> {
+    app.combat
+        .clone()
+        .filter(|_| app.in_combat)
+        .ok_or_else(|| Failure::invalid("No fight going on.".to_string()))
+}
+
+/// resolves the current phase, unless the campaign is already over
+fn advance_phase(app: &mut App) -> Result<String, Failure> {
+    if let Some(ending) = game_over(app) {
+        return Err(Failure {
+            code: "game_over",
+            message: format!("Game over: {ending}."),
+        });
+    }
+    allow(app, Action::Advance)?;
+    advance(app).map_err(Failure::invalid)?;
+    Ok(app.game_text.clone())
+}
+
+/// runs a game command against the rules, returning what happened
+fn execute(app: &mut App, request: Request) -> Result<String, Failure> {
+    match request {
+        Request::State | Request::Log | Request::Quit => Ok(String::new()),
+        Request::Advance => advance_phase(app),
+        Request::Leap { destination } => {
+            if app.phase != Phase::Leap {
+                return Err(Failure::not_allowed(format!(
+                    "Can only leap during the Leap phase, not the {} phase.",
+                    app.phase
+                )));
+            }
+            if let Some(destination) = destination {
+                if destination >= app.sector.choices.len() {
+                    return Err(Failure::invalid(format!(
+                        "No destination {destination}, there are {} in reach.",
+                        app.sector.choices.len()
+                    )));
+                }
+                app.sector.selected = destination;
+            }
+            advance_phase(app)
+        }
+        Request::Attack { scout, target } => {
+            allow(app, Action::Attack)?;
+            let mut combat = fight(app)?;
+            let result = scout_turn(app, &mut combat, scout, target);
+            app.combat = Some(combat);
+            result.map_err(Failure::invalid)
+        }
+        Request::Laser { target } => {
+            allow(app, Action::Laser)?;
+            let mut combat = fight(app)?;
+            let result = laser_turn(app, &mut combat, target);
+            app.combat = Some(combat);
+            result.map_err(Failure::invalid)
+        }
+        Request::EnemyTurn => {
+            allow(app, Action::EnemyTurn)?;
+            let mut combat = fight(app)?;
+            if combat.scout_half {
+                return Err(Failure::invalid(
+                    "The scouts haven't finished their turn.".to_string(),
+                ));
+            }
+            enemy_turn(&mut combat, app);
+            combat.log.push(combat.combat_text.clone());
+            let text = combat.combat_text.clone();
+            app.combat = Some(combat);
+            Ok(text)
+        }
+        Request::AutoResolve { policy } => {
+            allow(app, Action::AutoResolve)?;
+            fight(app)?;
+            let policy = match policy {
+                Some(policy) => AutoPolicy::parse(&policy).map_err(Failure::bad_request)?,
+                None => app.auto_policy,
+            };
+            Ok(auto_resolve(app, policy, true))
+        }
+        Request::Recall { scout } => {
+            allow(app, Action::Recall)?;
+            let mut combat = fight(app)?;
+            let result = recall_turn(app, &mut combat, scout);
+            app.combat = Some(combat);
+            result.map_err(Failure::invalid)
+        }
+        Request::EmergencyJump => {
+            allow(app, Action::EmergencyJump)?;
+            emergency_jump(app).map_err(Failure::invalid)
+        }
+        Request::Event { choice } => {
+            let Some(mut event) = app.event else {
+                return Err(Failure::invalid(
+                    "No event waiting on a choice.".to_string(),
+                ));
+            };
+            if choice >= event.card.choices.len() {
+                return Err(Failure::invalid(format!(
+                    "No choice {choice}, {} has {}.",
+                    event.card.name,
+                    event.card.choices.len()
+                )));
+            }
+            event.selected = choice;
+            app.event = Some(event);
+            resolve_event(app).map_err(Failure::invalid)
+        }
+        Request::Repair { jobs } => {
+            allow(app, Action::Repair)?;
+            let mut plan = RepairPlan::new(app);
+            match jobs {
+                Some(jobs) => {
+                    for job in jobs {
+                        let entries = plan.entries.len();
+                        let entry = plan.entries.get_mut(job).ok_or_else(|| {
+                            Failure::invalid(format!("No job {job}, the plan has {entries}."))
+                        })?;
+                        entry.queued = true;
+                    }
+                }
+                None => plan.suggest(&app.parts),
+            }
+            apply_plan(app, &plan).map_err(Failure::invalid)
+        }
+        Request::Upgrade { subsystem } => {
+            allow(app, Action::Upgrade)?;
+            if subsystem >= SUBSYSTEM_NAMES.len() {
+                return Err(Failure::invalid(format!(
+                    "No subsystem {subsystem}, they run 0 (hull) to {}.",
+                    SUBSYSTEM_NAMES.len() - 1
+                )));
+            }
+            subsystem_upgrade(app, subsystem).map_err(Failure::invalid)
+        }
+        Request::Trade { offer } => {
+            allow(app, Action::Trade)?;
+            trade(app, offer).map_err(Failure::invalid)
+        }
+    }
+}
+
+/// entries at the end of `after` that weren't in `before` - everything, if `after` started over
+fn added<T: PartialEq + Clone>(before: &[T], after: &[T]) -> Vec<T> {
+    if after.starts_with(before) {
+        after[before.len()..].to_vec()
+    } else {
+        after.to_vec()
+    }
+}
+
+/// every command the rules would take right now, with the choices each one has
+/// what the player could do from the keys, minus anything they can't pay for
+fn legal_actions(app: &App) -> Vec<Value> {
+    let can = |action| check_action(app, action).is_ok();
+    let mut actions = Vec::new();
+    if game_over(app).is_none() && can(Action::Advance) {
+        if app.phase == Phase::Leap {
+            let destinations: Vec<usize> = (0..app.sector.choices.len()).collect();
+            actions.push(json!({"cmd": "leap", "destinations": destinations}));
+        }
+        actions.push(json!({"cmd": "advance"}));
+    }
+    if let Some(event) = app.event {
+        let choices: Vec<usize> = event
+            .card
+            .choices
+            .iter()
+            .enumerate()
+            .filter(|(_, choice)| {
+                let (fuel, parts) = choice.cost();
+                app.fuel >= fuel && app.parts.total() >= parts
+            })
+            .map(|(i, _)| i)
+            .collect();
+        actions.push(json!({"cmd": "event", "choices": choices}));
+    }
+    if let Some(combat) = app.combat.as_ref().filter(|_| app.in_combat) {
+        if combat.scout_half {
+            let targets: Vec<usize> = combat
+                .enemy_stats
+                .iter()
+                .enumerate()
+                .filter(|(i, x)| x.is_active() && !combat.shielded(*i))
+                .map(|(i, _)| i)
+                .collect();
+            let waiting: Vec<usize> = combat
+                .scout_formation
+                .iter()
+                .enumerate()
+                .filter(|(i, id)| !combat.scout_turns[*i] && !combat.recalled.contains(id))
+                .map(|(i, _)| i)
+                .collect();
+            let scouts: Vec<usize> = waiting
+                .iter()
+                .copied()
+                .filter(|i| {
+                    let id = combat.scout_formation[*i];
+                    let ship_ok = app.roster.scout(id).is_some_and(|x| {
+                        matches!(x.ship.damage, ShipDamage::Normal | ShipDamage::Half)
+                    });
+                    let pilot_ok = app
+                        .roster
+                        .pilot_of(id)
+                        .is_some_and(|x| x.status != PilotStatus::Kia);
+                    ship_ok && pilot_ok
+                })
+                .collect();
+            if can(Action::Attack) && !scouts.is_empty() && !targets.is_empty() {
+                actions.push(json!({"cmd": "attack", "scouts": scouts, "targets": targets}));
+            }
+            let laser_ready = !combat.laser_fired && combat.rounds > 1 && !combat.laser_jammed();
+            if can(Action::Laser) && laser_ready && !targets.is_empty() {
+                actions.push(json!({"cmd": "laser", "targets": targets}));
+            }
+            let recall_ok = recall_cost(&app.scout_bay)
+                .is_ok_and(|cost| app.parts.can_afford(Part::Avionics, cost));
+            if can(Action::Recall) && recall_ok && !waiting.is_empty() {
+                actions.push(json!({"cmd": "recall", "scouts": waiting}));
+            }
+        } else if can(Action::EnemyTurn) {
+            actions.push(json!({"cmd": "enemy_turn"}));
+        }
+        if can(Action::AutoResolve) {
+            actions.push(json!({"cmd": "auto_resolve", "policies": ["lead", "weakest"]}));
+        }
+        if let Ok(extra) = check_emergency_jump(app) {
+            actions.push(json!({
+                "cmd": "emergency_jump",
+                "fuel": extra + 1,
+                "pilots_lost": stranded_pilots(app),
+            }));
+        }
+    }
+    if can(Action::Repair) {
+        let plan = RepairPlan::new(app);
+        let jobs: Vec<Value> = plan
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| app.parts.can_afford(x.part, x.cost))
+            .map(|(i, x)| json!({"job": i, "label": x.label, "part": x.part, "cost": x.cost}))
+            .collect();
+        if !jobs.is_empty() {
+            actions.push(json!({"cmd": "repair", "jobs": jobs}));
+        }
+    }
+    if can(Action::Upgrade) {
+        let subsystems: Vec<Value> = SUBSYSTEM_NAMES
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                let cost = tier_cost(upgrade_tier(app, i))?;
+                let part = Part::for_subsystem(i);
+                app.parts
+                    .can_afford(part, cost)
+                    .then(|| json!({"subsystem": i, "name": name, "part": part, "cost": cost}))
+            })
+            .collect();
+        if !subsystems.is_empty() {
+            actions.push(json!({"cmd": "upgrade", "subsystems": subsystems}));
+        }
+    }
+    if can(Action::Trade) {
+        let trades: Vec<Value> = offers(app)
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, price))| app.parts.total() >= *price)
+            .map(|(i, (trade, price))| {
+                json!({"offer": i, "trade": trade.to_string(), "price": price})
+            })
+            .collect();
+        if !trades.is_empty() {
+            actions.push(json!({"cmd": "trade", "offers": trades}));
+        }
+    }
+    actions
+}
+
+/// everything the player could see about the campaign, plus what they can do next
+/// systems in reach only show what the sensors make out, like the Map tab
+fn state(app: &App) -> Value {
+    let detail = forecast_detail(&app.sensors);
+    let destinations: Vec<Value> = app
+        .sector
+        .choices
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            json!({
+                "destination": i,
+                "name": x.name,
+                "x": x.x,
+                "y": x.y,
+                "readings": x.readings(detail),
+            })
+        })
+        .collect();
+    json!({
+        "name": app.name,
+        "seed": app.recording.seed,
+        "phase": app.phase,
+        "exit_condition": app.phase.exit_condition(),
+        "message": app.game_text,
+        "game_over": game_over(app),
+        "leaps_since_incident": app.leaps_since_incident,
+        "leaps_logged": app.log.len(),
+        "fuel": app.fuel,
+        "parts": app.parts,
+        "hull_damage": app.hull_damage,
+        "max_hull": max_hull(app.hull_upgrade),
+        "hull_upgrade": app.hull_upgrade,
+        "engine": app.engine,
+        "mining_laser": app.mining_laser,
+        "scout_bay": app.scout_bay,
+        "sick_bay": app.sick_bay,
+        "sensors": app.sensors,
+        "roster": app.roster,
+        "current_leap": app.current_leap,
+        "laser_kills": app.laser_kills,
+        "rules": app.rules,
+        "boss_defeated": app.boss_defeated,
+        "system": app.sector.current,
+        "destinations": destinations,
+        "selected_destination": app.sector.selected,
+        "event": app.event,
+        "pursuers": app.pursuers,
+        "in_combat": app.in_combat,
+        "combat": app.combat,
+        "wreckage": app.wreckage,
+        "legal_actions": legal_actions(app),
+    })
+}
+
+/// runs one line from the bot and builds the reply
+/// game commands answer with what happened - the message, any rolls and combat log lines they
+/// added - and the state afterwards
+fn respond(app: &mut App, line: &str) -> Option<Value> {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            return Some(error_reply(
+                Value::Null,
+                Failure::bad_request(e.to_string()),
+            ))
+        }
+    };
+    let cmd = value.get("cmd").cloned().unwrap_or(Value::Null);
+    let request: Request = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => return Some(error_reply(cmd, Failure::bad_request(e.to_string()))),
+    };
+    match request {
+        Request::Quit => return None,
+        Request::State => return Some(json!({"type": "state", "state": state(app)})),
+        Request::Log => return Some(json!({"type": "log", "log": app.log})),
+        _ => {}
+    }
+    let rolls = app.current_leap.rolls.clone();
+    let combat_log = app
+        .combat
+        .as_ref()
+        .map(|x| x.log.clone())
+        .unwrap_or_default();
+    let result = execute(app, request);
+    advance_combat(app); // same as after a key press
+    let reply = match result {
+        Ok(message) => json!({
+            "type": "result",
+            "cmd": cmd,
+            "message": message,
+            "rolls": added(&rolls, &app.current_leap.rolls),
+            "combat_log": added(&combat_log, app.combat.as_ref().map_or(&[][..], |x| &x.log)),
+            "state": state(app),
+        }),
+        Err(failure) => error_reply(cmd, failure),
+    };
+    Some(reply)
+}
+
+fn error_reply(cmd: Value, failure: Failure) -> Value {
+    json!({"type": "error", "cmd": cmd, "error": failure})
+}
+
+/// plays the campaign for a bot over stdin and stdout, one JSON object per line each way
+/// says hello with the starting state, then answers every command until "quit" or end of input
+pub fn run(app: &mut App) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    let hello = json!({
+        "type": "hello",
+        "protocol": PROTOCOL_VERSION,
+        "state": state(app),
+    });
+    writeln!(out, "{hello}")?;
+    out.flush()?;
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(reply) = respond(app, &line) else {
+            break;
+        };
+        writeln!(out, "{reply}")?;
+        out.flush()?;
+    }
+    Ok(())
+}
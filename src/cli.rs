@@ -0,0 +1,452 @@
+use std::{collections::BTreeMap, env, fs};
+
+use serde_json::json;
+
+use crate::{
+    app::App,
+    gamerules::{
+        combat::AutoPolicy,
+        game_functions::threats_summary,
+        rules::{Difficulty, Rules},
+        threat::Threats,
+        Leap,
+    },
+    replay::Replay,
+    sim::{play_out, Outcome},
+};
+
+pub const USAGE: &str = "Usage: lostship [COMMAND] [OPTIONS]
+
+Commands:
+  play                  start a campaign in the terminal (the default)
+    --seed <n>            seed the dice, the same seed deals the same campaign
+    --difficulty <d>      easy, normal or hard
+    --rules <file>        rules file of `name = value` lines, applied over the difficulty
+    --load <save>         carry on a saved campaign - saves are the replay files
+    --no-color            draw in the terminal's default colours (so does NO_COLOR=1)
+  sim                   play campaigns with nobody at the keys and print how they went
+    --games <n>           campaigns to play (10)
+    --seed <n>            seed of the first campaign, the rest count up from it
+    --leaps <n>           stop each campaign after this many leaps (30)
+    --policy <p>          auto-resolve policy for fights, lead or weakest
+    --difficulty <d>      as for play
+    --rules <file>        as for play
+  bot                   play over stdin and stdout, one JSON command or reply per line
+    --seed, --difficulty, --rules and --load as for play
+  replay <file>         watch a replay
+  export <save>         print a campaign's leap log
+    --format <f>          md, csv or json (md)
+  validate <save>       check a save loads and plays through to its last command
+  help                  show this message
+
+Rules files can set boss_after_leaps, boss_threshold, threat_modifier and starting_fuel.";
+
+/// what the command line asked for
+#[derive(Debug, Clone)]
+pub enum Command {
+    Play(PlayOptions),
+    Bot(PlayOptions),
+    Sim(SimOptions),
+    Replay(String),
+    Export(String, Format),
+    Validate(String),
+    Help,
+}
+
+/// difficulty preset and rules file, in the order they're applied
+#[derive(Debug, Clone, Default)]
+pub struct RuleOptions {
+    pub difficulty: Difficulty,
+    pub file: Option<String>,
+}
+
+impl RuleOptions {
+    pub fn load(&self) -> Result<Rules, String> {
+        let mut rules = Rules::for_difficulty(self.difficulty);
+        if let Some(path) = &self.file {
+            let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            rules
+                .apply_text(&text)
+                .map_err(|e| format!("{path}: {e}"))?;
+        }
+        Ok(rules)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayOptions {
+    pub seed: Option<u64>,
+    pub rules: RuleOptions,
+    pub load: Option<String>,
+    pub no_color: bool,
+}
+
+impl PlayOptions {
+    /// the campaign to play - a loaded save, or a new one on the given seed and rules
+    pub fn app(&self) -> Result<App, String> {
+        let mut app = match &self.load {
+            Some(path) => App::resume(load_save(path)?).map_err(|e| format!("{path}: {e}"))?,
+            None => App::new(self.seed.unwrap_or_else(rand::random), self.rules.load()?),
+        };
+        // https://no-color.org - any non-empty value turns colour off
+        app.no_color = self.no_color || env::var("NO_COLOR").is_ok_and(|x| !x.is_empty());
+        Ok(app)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimOptions {
+    pub games: u64,
+    pub seed: Option<u64>,
+    pub leaps: usize,
+    pub policy: AutoPolicy,
+    pub rules: RuleOptions,
+}
+
+/// export formats for a campaign's leap log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Csv,
+    Json,
+}
+
+/// options for one command, `--name value`, `--name=value` or a bare `--switch`
+/// anything else is a positional argument
+struct Options {
+    positional: Vec<String>,
+    flags: BTreeMap<String, String>,
+}
+
+impl Options {
+    /// splits the arguments after the command, rejecting flags it doesn't know
+    fn parse(args: &[String], valued: &[&str], switches: &[&str]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut flags = BTreeMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+            let (name, inline) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let value = if valued.contains(&name) {
+                inline
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| format!("--{name} needs a value."))?
+            } else if switches.contains(&name) && inline.is_none() {
+                String::new()
+            } else {
+                return Err(format!("Unknown option --{name}."));
+            };
+            flags.insert(name.to_string(), value);
+        }
+        Ok(Options { positional, flags })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).map(String::as_str)
+    }
+
+    /// a numeric option, if it was given
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.get(name)
+            .map(|x| {
+                x.parse()
+                    .map_err(|_| format!("--{name} needs a number, not '{x}'."))
+            })
+            .transpose()
+    }
+
+    fn rules(&self) -> Result<RuleOptions, String> {
+        Ok(RuleOptions {
+            difficulty: self
+                .get("difficulty")
+                .map(Difficulty::parse)
+                .transpose()?
+                .unwrap_or_default(),
+            file: self.get("rules").map(str::to_string),
+        })
+    }
+
+    /// the one positional argument a command takes
+    fn single(&self, command: &str, what: &str) -> Result<String, String> {
+        match self.positional.as_slice() {
+            [one] => Ok(one.clone()),
+            [] => Err(format!("{command} needs a {what}.")),
+            _ => Err(format!("{command} takes a single {what}.")),
+        }
+    }
+}
+
+/// reads the command line (without the program name)
+/// no arguments at all starts a new campaign, just like `play`
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Play(PlayOptions::default()));
+    };
+    match command.as_str() {
+        // a bot has no use for colours, otherwise it starts a campaign the same way
+        "play" | "bot" => {
+            let switches: &[&str] = if command == "play" {
+                &["no-color"]
+            } else {
+                &[]
+            };
+            let options = Options::parse(rest, &["seed", "difficulty", "rules", "load"], switches)?;
+            if let Some(extra) = options.positional.first() {
+                return Err(format!("{command} doesn't take '{extra}'."));
+            }
+            let play = PlayOptions {
+                seed: options.number("seed")?,
+                rules: options.rules()?,
+                load: options.get("load").map(str::to_string),
+                no_color: options.has("no-color"),
+            };
+            let fresh = ["seed", "difficulty", "rules"];
+            if play.load.is_some() && fresh.iter().any(|x| options.has(x)) {
+                return Err(
+                    "--load carries on with the save's own seed and rules, drop --seed, --difficulty and --rules."
+                        .to_string(),
+                );
+            }
+            if command == "bot" {
+                return Ok(Command::Bot(play));
+            }
+            Ok(Command::Play(play))
+        }
+        "sim" => {
+            let options = Options::parse(
+                rest,
+                &["games", "seed", "leaps", "policy", "difficulty", "rules"],
+                &[],
+            )?;
+            if let Some(extra) = options.positional.first() {
+                return Err(format!("sim doesn't take '{extra}'."));
+            }
+            let policy = options
+                .get("policy")
+                .map(AutoPolicy::parse)
+                .transpose()?
+                .unwrap_or_default();
+            Ok(Command::Sim(SimOptions {
+                games: options.number("games")?.unwrap_or(10),
+                seed: options.number("seed")?,
+                leaps: options.number("leaps")?.unwrap_or(30),
+                policy,
+                rules: options.rules()?,
+            }))
+        }
+        // --replay is how replays were watched before there were commands
+        "replay" | "--replay" => {
+            let options = Options::parse(rest, &[], &[])?;
+            Ok(Command::Replay(options.single("replay", "replay file")?))
+        }
+        "export" => {
+            let options = Options::parse(rest, &["format"], &[])?;
+            let format = match options.get("format") {
+                None | Some("md") => Format::Markdown,
+                Some("csv") => Format::Csv,
+                Some("json") => Format::Json,
+                Some(other) => return Err(format!("Unknown format '{other}' (md, csv or json).")),
+            };
+            Ok(Command::Export(options.single("export", "save")?, format))
+        }
+        "validate" => {
+            let options = Options::parse(rest, &[], &[])?;
+            Ok(Command::Validate(options.single("validate", "save")?))
+        }
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("Unknown command '{other}'.")),
+    }
+}
+
+/// reads a save, naming the file in any error
+fn load_save(path: &str) -> Result<Replay, String> {
+    Replay::load(path).map_err(|e| format!("{path}: {e}"))
+}
+
+/// plays a batch of campaigns headless and prints a line for each, then the averages
+pub fn sim(options: &SimOptions) -> Result<(), String> {
+    let rules = options.rules.load()?;
+    let first = options.seed.unwrap_or_else(rand::random);
+    let mut outcomes: Vec<Outcome> = Vec::new();
+    println!("seed,leaps,fuel,parts,hull_damage,pilots_lost,kills,boss_defeated,ending");
+    for game in 0..options.games {
+        let mut app = App::new(first.wrapping_add(game), rules.clone());
+        let outcome = play_out(&mut app, options.leaps, options.policy);
+        println!(
+            "{},{},{},{},{},{},{},{},{}",
+            outcome.seed,
+            outcome.leaps,
+            outcome.fuel,
+            outcome.parts,
+            outcome.hull_damage,
+            outcome.pilots_lost,
+            outcome.kills,
+            outcome.boss_defeated,
+            outcome.ending
+        );
+        outcomes.push(outcome);
+    }
+    if outcomes.is_empty() {
+        return Ok(());
+    }
+    let average =
+        |f: fn(&Outcome) -> f64| outcomes.iter().map(f).sum::<f64>() / outcomes.len() as f64;
+    eprintln!(
+        "{} campaign(s): {:.1} leaps, {:.1} pilots lost, {:.1} kills on average, capital ship beaten {} time(s), {} reached the leap limit.",
+        outcomes.len(),
+        average(|x| x.leaps as f64),
+        average(|x| x.pilots_lost as f64),
+        average(|x| x.kills as f64),
+        outcomes.iter().filter(|x| x.boss_defeated).count(),
+        outcomes.iter().filter(|x| x.ending == "leap limit").count(),
+    );
+    Ok(())
+}
+
+/// enemies met on a leap, leaving out the placeholder for a clear sector
+fn threats_text(leap: &Leap) -> String {
+    let threats: Vec<Threats> = leap
+        .threats
+        .iter()
+        .filter(|x| **x != Threats::None)
+        .cloned()
+        .collect();
+    if threats.is_empty() {
+        "none".to_string()
+    } else {
+        threats_summary(&threats)
+    }
+}
+
+/// kills on a leap as "pilot: model" pairs
+fn kills_text(app: &App, leap: &Leap) -> Vec<String> {
+    leap.kills
+        .iter()
+        .map(|(pilot, model)| format!("{}: {model}", app.roster.pilot_name(*pilot)))
+        .collect()
+}
+
+/// quotes a csv field if it has to be
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// prints a save's leap log in the given format
+pub fn export(path: &str, format: Format) -> Result<(), String> {
+    let replay = load_save(path)?;
+    let app = App::resume(replay.clone()).map_err(|e| format!("{path}: {e}"))?;
+    let text = match format {
+        Format::Markdown => {
+            let mut text = format!(
+                "# {} - seed {}\n\n{} leaps, {} fuel, {} parts, {} hull damage, now in the {} phase.\n\n",
+                app.name,
+                replay.seed,
+                app.log.len(),
+                app.fuel,
+                app.parts.total(),
+                app.hull_damage,
+                app.phase
+            );
+            text +=
+                "| Leap | Combat Rounds | Parts Found | Fuel Found | Threats | Kills | Events |\n";
+            text += "|---|---|---|---|---|---|---|\n";
+            for leap in &app.log {
+                text += &format!(
+                    "| {} | {} | {} | {} | {} | {} | {} |\n",
+                    leap.number,
+                    leap.combat_rounds,
+                    leap.parts_found,
+                    leap.fuel_found,
+                    threats_text(leap),
+                    kills_text(&app, leap).join(", "),
+                    leap.events.join("; ").replace('|', "\\|")
+                );
+            }
+            text
+        }
+        Format::Csv => {
+            let mut text =
+                "leap,combat_rounds,parts_found,fuel_found,threats,kills,events\n".to_string();
+            for leap in &app.log {
+                let fields = [
+                    leap.number.to_string(),
+                    leap.combat_rounds.to_string(),
+                    leap.parts_found.to_string(),
+                    leap.fuel_found.to_string(),
+                    threats_text(leap),
+                    kills_text(&app, leap).join("; "),
+                    leap.events.join("; "),
+                ];
+                let row: Vec<String> = fields.iter().map(|x| csv_field(x)).collect();
+                text += &row.join(",");
+                text.push('\n');
+            }
+            text
+        }
+        Format::Json => {
+            let rules = &replay.rules;
+            let leaps: Vec<serde_json::Value> = app
+                .log
+                .iter()
+                .map(|leap| {
+                    json!({
+                        "leap": leap.number,
+                        "combat_rounds": leap.combat_rounds,
+                        "parts_found": leap.parts_found,
+                        "fuel_found": leap.fuel_found,
+                        "threats": threats_text(leap),
+                        "kills": kills_text(&app, leap),
+                        "events": leap.events,
+                    })
+                })
+                .collect();
+            let campaign = json!({
+                "seed": replay.seed,
+                "rules": {
+                    "boss_after_leaps": rules.boss_after_leaps,
+                    "boss_threshold": rules.boss_threshold,
+                    "threat_modifier": rules.threat_modifier,
+                    "starting_fuel": rules.starting_fuel,
+                },
+                "fuel": app.fuel,
+                "parts": app.parts.total(),
+                "hull_damage": app.hull_damage,
+                "phase": app.phase.to_string(),
+                "leaps": leaps,
+            });
+            serde_json::to_string_pretty(&campaign).map_err(|e| e.to_string())? + "\n"
+        }
+    };
+    print!("{text}");
+    Ok(())
+}
+
+/// checks a save parses and plays through to its last command, and says where it ends up
+pub fn validate(path: &str) -> Result<(), String> {
+    let replay = load_save(path)?;
+    let commands = replay.commands.len();
+    let seed = replay.seed;
+    let app = App::resume(replay).map_err(|e| format!("{path}: {e}"))?;
+    println!(
+        "{path}: ok - seed {seed}, {commands} commands, {} leaps logged, now in the {} phase with {} fuel and {} parts.",
+        app.log.len(),
+        app.phase,
+        app.fuel,
+        app.parts.total()
+    );
+    Ok(())
+}
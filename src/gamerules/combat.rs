@@ -1,10 +1,16 @@
+use core::fmt;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
 use crate::app::App;
 
 use super::{
-    pilot::{PilotStatus, Rank},
-    roll,
-    ship::{Scout, ShipDamage, Status},
-    threat::{Fighter, Threats},
+    dice::{Modifier, RollRecord},
+    parts::Part,
+    pilot::{Pilot, PilotStatus, Rank},
+    ship::{laser_tier_progress, FlightPosition, Ship, ShipDamage, Status, SubSystem},
+    threat::{fresh_fighter, wreckage, Component, Fighter, FighterState, Threats},
 };
 
 pub enum Targets {
@@ -22,14 +28,50 @@ pub enum Targets {
     Sensors,
 }
 
+impl Targets {
+    /// flight position for targets that hit a scout
+    pub fn flight_position(&self) -> Option<FlightPosition> {
+        match self {
+            Targets::LeadScout => Some(FlightPosition::Lead),
+            Targets::SecondScout => Some(FlightPosition::Second),
+            Targets::ThirdScout => Some(FlightPosition::Third),
+            Targets::FourthScout => Some(FlightPosition::Fourth),
+            Targets::FifthScout => Some(FlightPosition::Fifth),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Targets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Targets::Superficial => "Superficial",
+            Targets::FifthScout => "Fifth scout",
+            Targets::FourthScout => "Fourth scout",
+            Targets::ThirdScout => "Third scout",
+            Targets::SecondScout => "Second scout",
+            Targets::LeadScout => "Lead scout",
+            Targets::Hull => "Hull",
+            Targets::Engines => "Engines",
+            Targets::MiningLaser => "Mining laser",
+            Targets::ScoutingBay => "Scout bay",
+            Targets::SickBay => "Sick bay",
+            Targets::Sensors => "Sensors",
+        };
+        write!(f, "{printable}")
+    }
+}
+
 /// tracks all the information for combat phase
-/// formation fields are predefined arrays because it is not possible to field more than 6 ships
+/// scout_formation holds roster ids of the launched scouts, lead scout first
 /// scout_half field tracks if scouts are going or if enemy is taking the turn, scouts go first
 /// scout_turns/enemy_turns tracks when each ship takes it's turn
-#[derive(Debug, Clone)]
+/// recalled scouts stay in scout_formation so the turn indices line up, but sit out the fight
+#[derive(Debug, Clone, Serialize)]
 pub struct Combat {
     pub rounds: u64,
-    pub scout_formation: Vec<Scout>,
+    pub scout_formation: Vec<u64>,
+    pub recalled: Vec<u64>, // scout ids pulled back to the scout bay
     pub enemy_formation: Vec<Threats>,
     pub enemy_stats: Vec<Fighter>,
     pub scout_turns: Vec<bool>,
@@ -37,43 +79,129 @@ pub struct Combat {
     pub scout_half: bool,
     pub laser_fired: bool, // has mining laser been fired this turn?
     pub combat_text: String,
+    pub log: Vec<String>, // everything that has happened this fight
 }
 
-/// logic for scout attack - modifies roll based on pilot rank and returns damage
-pub fn scout_attack(scout: &Scout) -> u64 {
-    let mut modifier: i64 = match scout.ship.damage {
-        ShipDamage::Normal => 0,
-        ShipDamage::Half => -1,
-        _ => 0,
-    };
-    match scout.pilot.rank {
+impl Combat {
+    /// an active jammer keeps the mining laser from locking on
+    pub fn laser_jammed(&self) -> bool {
+        self.enemy_stats
+            .iter()
+            .any(|x| x.model == Threats::Jammer && x.is_active())
+    }
+
+    /// active capital ship sections of the given kind
+    fn capital_active(&self, component: Component) -> bool {
+        self.enemy_stats
+            .iter()
+            .any(|x| x.model == Threats::Capital(component) && x.is_active())
+    }
+
+    /// capital ship guns and engines can't be hit while its shields are up
+    pub fn shielded(&self, enemy_pos: usize) -> bool {
+        let protected = self.enemy_stats.get(enemy_pos).is_some_and(|x| {
+            matches!(
+                x.model,
+                Threats::Capital(Component::Guns) | Threats::Capital(Component::Engines)
+            )
+        });
+        protected && self.capital_active(Component::Shields)
+    }
+
+    /// stage of the boss fight - 1 shields up, 2 shields down, 3 guns silenced
+    /// None when there's no capital ship left in the fight
+    pub fn boss_phase(&self) -> Option<u8> {
+        if self.capital_active(Component::Shields) {
+            Some(1)
+        } else if self.capital_active(Component::Guns) {
+            Some(2)
+        } else if self.capital_active(Component::Engines) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    /// every active carrier launches a fresh MK1 into the fight, returns how many launched
+    /// a capital ship with its guns silenced throws its last fighters at us the same way, but
+    /// only keeps up to 3 MK1s flying at once
+    pub fn launch_from_carriers(&mut self) -> usize {
+        let mk1s = self
+            .enemy_stats
+            .iter()
+            .filter(|x| x.model == Threats::Mk1 && x.is_active())
+            .count();
+        let carriers = self
+            .enemy_stats
+            .iter()
+            .filter(|x| x.model == Threats::Carrier && x.is_active())
+            .count()
+            + usize::from(self.boss_phase() == Some(3) && mk1s < 3);
+        for _ in 0..carriers {
+            self.enemy_formation.push(Threats::Mk1);
+            self.enemy_stats.push(Fighter::mk1());
+        }
+        carriers
+    }
+}
+
+/// modifiers to a scout's attack roll from the ship's damage and the pilot's rank
+pub fn attack_modifiers(ship: &Ship, pilot: &Pilot) -> Vec<Modifier> {
+    let mut modifiers = Vec::new();
+    if ship.damage == ShipDamage::Half {
+        modifiers.push(Modifier::new("Scout at 50%", -1));
+    }
+    match pilot.rank {
         Rank::Rookie => {}
-        Rank::Veteran => modifier += 1,
-        Rank::Ace => modifier += 2,
+        Rank::Veteran => modifiers.push(Modifier::new("Veteran", 1)),
+        Rank::Ace => modifiers.push(Modifier::new("Ace", 2)),
     };
-    let attack_result = roll(6) + modifier;
-    if attack_result == 5 || (attack_result == 6 && scout.pilot.status == PilotStatus::Injured) {
+    modifiers
+}
+
+/// scout attack table - 5 deals 1 damage, 6 deals 2 (only 1 if the pilot is injured)
+pub fn attack_damage(attack_result: i64, status: &PilotStatus) -> u64 {
+    if attack_result == 5 || (attack_result == 6 && *status == PilotStatus::Injured) {
         1
-    } else if attack_result == 6 && scout.pilot.status == PilotStatus::Normal {
+    } else if attack_result == 6 && *status == PilotStatus::Normal {
         2
     } else {
         0
     }
 }
 
+/// logic for scout attack - modifies roll based on pilot rank and returns damage
+pub fn scout_attack(ship: &Ship, pilot: &Pilot) -> (u64, RollRecord) {
+    let mut record = RollRecord::roll("Scout attack", 1, 6);
+    for modifier in attack_modifiers(ship, pilot) {
+        record.modifier(&modifier.source, modifier.value);
+    }
+    let attack_result = record.total();
+    let damage = attack_damage(attack_result, &pilot.status);
+    let outcome = if attack_result == 6 && pilot.status == PilotStatus::Injured {
+        "1 damage (injured pilot)".to_string()
+    } else {
+        format!("{damage} damage")
+    };
+    (damage, record.outcome(&outcome))
+}
+
 /// enemy attack success roll
-pub fn enemy_attack() -> bool {
-    roll(6) > 3
+pub fn enemy_attack() -> (bool, RollRecord) {
+    let record = RollRecord::roll("Enemy attack", 1, 6);
+    let hit = record.total() > 3;
+    (hit, record.outcome(if hit { "hit" } else { "miss" }))
 }
 
 /// logic for enemy targeting - handles 1st round, 2nd round and after
-pub fn enemy_targeting(combat: &Combat) -> Targets {
-    let roll_result = if combat.rounds > 1 {
-        roll(6) + roll(6)
+pub fn enemy_targeting(combat: &Combat) -> (Targets, RollRecord) {
+    let record = if combat.rounds > 1 {
+        RollRecord::roll("Enemy targeting", 2, 6)
     } else {
-        roll(6)
+        RollRecord::roll("Enemy targeting (round 1)", 1, 6)
     };
-    if roll_result == 1 {
+    let roll_result = record.total();
+    let target = if roll_result == 1 {
         Targets::Superficial
     } else if roll_result == 2 {
         Targets::FifthScout
@@ -99,22 +227,35 @@ pub fn enemy_targeting(combat: &Combat) -> Targets {
         Targets::Sensors
     } else {
         Targets::Hull
+    };
+    let outcome = target.to_string();
+    (target, record.outcome(&outcome))
+}
+
+/// archetypes that skip the targeting roll - bombers only go for the hull,
+/// interceptors always go for the lead scout
+pub fn fixed_target(model: &Threats) -> Option<Targets> {
+    match model {
+        Threats::Bomber => Some(Targets::Hull),
+        Threats::Interceptor => Some(Targets::LeadScout),
+        _ => None,
     }
 }
 
 /// logic for damaging scout on hit
-pub fn scout_damage(scout: &mut Scout) -> String {
-    let roll_result = roll(6);
-    if roll_result == 1 {
+pub fn scout_damage(ship: &mut Ship, pilot: &mut Pilot) -> (String, RollRecord) {
+    let record = RollRecord::roll("Scout damage", 1, 6);
+    let roll_result = record.total();
+    let damage_text = if roll_result == 1 {
         "Superficial damage.".to_string()
     } else if roll_result == 2 {
-        match scout.pilot.status {
+        match pilot.status {
             PilotStatus::Normal => {
-                scout.pilot.status = PilotStatus::Injured;
+                pilot.status = PilotStatus::Injured;
                 "Pilot injured".to_string()
             }
             PilotStatus::Injured => {
-                scout.pilot.status = PilotStatus::Kia;
+                pilot.status = PilotStatus::Kia;
                 "Injured pilot KIA".to_string()
             }
             _ => {
@@ -122,54 +263,53 @@ pub fn scout_damage(scout: &mut Scout) -> String {
             }
         }
     } else if roll_result == 3 {
-        scout.pilot.status = PilotStatus::Kia;
+        pilot.status = PilotStatus::Kia;
         "Pilot KIA".to_string()
     } else if roll_result == 4 {
-        match scout.ship.damage {
+        match ship.damage {
             ShipDamage::Normal => {
-                scout.ship.damage = ShipDamage::Half;
+                ship.damage = ShipDamage::Half;
                 "Scout at 50% damage".to_string()
             }
             ShipDamage::Half => {
-                scout.ship.damage = ShipDamage::Destroyed;
+                ship.damage = ShipDamage::Destroyed;
                 "Damaged scout is destroyed".to_string()
             }
             _ => "...".to_string(), //NOTE: shouldn't land here
         }
     } else if roll_result == 5 {
-        scout.ship.damage = ShipDamage::Inoperable;
+        ship.damage = ShipDamage::Inoperable;
         "Scout Inoperable, recalling now...".to_string()
     } else {
-        scout.pilot.status = PilotStatus::Kia;
-        scout.ship.damage = ShipDamage::Destroyed;
+        pilot.status = PilotStatus::Kia;
+        ship.damage = ShipDamage::Destroyed;
         "Scout destroyed, pilot KIA".to_string()
-    }
+    };
+    let record = record.outcome(&damage_text);
+    (damage_text, record)
 }
 
-/// logic for mining laser attack
-pub fn mining_laser(upgraded: bool) -> u64 {
-    let mut roll_result = roll(6);
-    if upgraded {
-        roll_result += 1;
-    }
+/// mining laser table - 4-5 deals 1 damage, 6 deals 2, 7+ (upgraded only) deals 3
+pub fn laser_damage(roll_result: i64) -> u64 {
     if (4..=5).contains(&roll_result) {
         1
     } else if roll_result == 6 {
         2
-    } else if roll_result == 7 {
+    } else if roll_result >= 7 {
         3
     } else {
         0
     }
 }
 
-/// handles subtraction for enemy damage, protects for overflow
-pub fn enemy_damage(damage: u64, hp: u64) -> u64 {
-    if hp < damage {
-        0
-    } else {
-        hp - damage
+/// logic for mining laser attack, each upgrade tier adds 1 to the roll
+pub fn mining_laser(tier: u8) -> (u64, RollRecord) {
+    let mut record = RollRecord::roll("Mining laser", 1, 6);
+    if tier > 0 {
+        record.modifier(&format!("Laser tier {tier}"), tier as i64);
     }
+    let damage = laser_damage(record.total());
+    (damage, record.outcome(&format!("{damage} damage")))
 }
 
 /// function for rendering enemy turns in combat
@@ -177,109 +317,83 @@ pub fn enemy_turn(combat: &mut Combat, app: &mut App) {
     for (i, turn) in combat.enemy_turns.clone().iter().enumerate() {
         if !turn {
             combat.enemy_turns[i] = true;
-            if combat.enemy_stats[i].hp == 0 || combat.enemy_stats[i].fuel == 0 {
-                continue; // stops from hanging on dead fighter
+            if !combat.enemy_stats[i].is_active() {
+                continue; // stops from hanging on dead or retreated fighter
             }
             combat.combat_text = String::new();
             let guns = combat.enemy_stats[i].guns;
+            let model = combat.enemy_stats[i].model.clone();
             for _ in 0..guns {
-                if enemy_attack() {
-                    let target = enemy_targeting(combat);
-                    match target {
-                        Targets::Superficial => {
-                            combat.combat_text += &format!(
-                                "Enemy {} deals superficial damage!  ",
-                                combat.enemy_stats[i].model
-                            );
-                        }
-                        // TODO: check for hull damage instead
-                        Targets::FifthScout => {
-                            let damage_text = scout_damage(&mut combat.scout_formation[4]);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages {}.  Scout {}  ",
-                                combat.enemy_stats[i].model,
-                                combat.scout_formation[4].ship.name,
-                                damage_text
-                            );
+                let (hit, record) = enemy_attack();
+                app.current_leap.rolls.push(record);
+                if hit {
+                    let target = match fixed_target(&model) {
+                        Some(target) => target,
+                        None => {
+                            let (target, record) = enemy_targeting(combat);
+                            app.current_leap.rolls.push(record);
+                            target
                         }
-                        Targets::FourthScout => {
-                            let damage_text = scout_damage(&mut combat.scout_formation[3]);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages {}.  Scout {}  ",
-                                combat.enemy_stats[i].model,
-                                combat.scout_formation[3].ship.name,
-                                damage_text
-                            );
+                    };
+                    if let Some(position) = target.flight_position() {
+                        // nobody flying that slot - the shot goes through to the colony ship
+                        let scout_id = combat.scout_formation.iter().copied().find(|id| {
+                            !combat.recalled.contains(id)
+                                && app
+                                    .roster
+                                    .scout(*id)
+                                    .is_some_and(|x| x.position == position)
+                        });
+                        match scout_id.and_then(|id| app.roster.crewed_mut(id)) {
+                            Some((scout, pilot)) => {
+                                let (damage_text, record) = scout_damage(&mut scout.ship, pilot);
+                                combat.combat_text += &format!(
+                                    "Enemy {} damages {}.  Scout {}  ",
+                                    model, scout.ship.name, damage_text
+                                );
+                                app.current_leap.rolls.push(record);
+                            }
+                            None => {
+                                app.hull_damage += 1;
+                                combat.combat_text += &format!(
+                                    "Enemy {model} finds no scout in the {position} slot and damages the hull.  "
+                                );
+                            }
                         }
-                        Targets::ThirdScout => {
-                            let damage_text = scout_damage(&mut combat.scout_formation[2]);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages {}.  Scout {}  ",
-                                combat.enemy_stats[i].model,
-                                combat.scout_formation[2].ship.name,
-                                damage_text
-                            );
-                        }
-                        Targets::SecondScout => {
-                            let damage_text = scout_damage(&mut combat.scout_formation[1]);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages {}.  Scout {}  ",
-                                combat.enemy_stats[i].model,
-                                combat.scout_formation[1].ship.name,
-                                damage_text
-                            );
-                        }
-                        Targets::LeadScout => {
-                            let damage_text = scout_damage(&mut combat.scout_formation[0]);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages {}.  Scout {}  ",
-                                combat.enemy_stats[i].model,
-                                combat.scout_formation[0].ship.name,
-                                damage_text
-                            );
+                        continue;
+                    }
+                    match target {
+                        Targets::Superficial => {
+                            combat.combat_text +=
+                                &format!("Enemy {model} deals superficial damage!  ");
                         }
                         Targets::Hull => {
                             app.hull_damage += 1;
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the hull.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text += &format!("Enemy {model} damages the hull.  ");
                         }
                         Targets::Engines => {
                             app.engine.status = subsystem_damage(&app.engine.status);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the engines.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text += &format!("Enemy {model} damages the engines.  ");
                         }
                         Targets::MiningLaser => {
                             app.mining_laser.status = subsystem_damage(&app.mining_laser.status);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the mining laser.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text +=
+                                &format!("Enemy {model} damages the mining laser.  ");
                         }
                         Targets::ScoutingBay => {
                             app.scout_bay.status = subsystem_damage(&app.scout_bay.status);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the scout bay.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text +=
+                                &format!("Enemy {model} damages the scout bay.  ");
                         }
                         Targets::SickBay => {
                             app.sick_bay.status = subsystem_damage(&app.sick_bay.status);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the sick bay.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text += &format!("Enemy {model} damages the sick bay.  ");
                         }
                         Targets::Sensors => {
                             app.sensors.status = subsystem_damage(&app.sensors.status);
-                            combat.combat_text += &format!(
-                                "Enemy {} damages the sensors.  ",
-                                combat.enemy_stats[i].model,
-                            );
+                            combat.combat_text += &format!("Enemy {model} damages the sensors.  ");
                         }
+                        _ => {} // scout targets handled above
                     }
                 } else {
                     combat.combat_text += "Miss!  ";
@@ -300,11 +414,442 @@ pub fn subsystem_damage(status: &Status) -> Status {
     }
 }
 
-// NOTE: there is probably a better way to handle this, I'll worry about that later
-/// copies scout changes from the combat struct back into the app struct
-pub fn combat_to_app(combat: &Combat, app: &mut App) {
-    for (i, scout) in combat.scout_formation.iter().enumerate() {
-        app.scouts[i] = scout.clone();
-        app.pilots[i] = scout.pilot.clone();
+/// announces a capital ship section going down, empty for everything else
+fn capital_destroyed_text(model: &Threats) -> &'static str {
+    match model {
+        Threats::Capital(Component::Shields) => "  The capital ship's shields collapse!",
+        Threats::Capital(Component::Guns) => {
+            "  The capital ship's guns fall silent - it's launching its last fighters!"
+        }
+        Threats::Capital(Component::Engines) => {
+            "  The capital ship's engines blow - it's breaking up!"
+        }
+        _ => "",
+    }
+}
+
+/// resolves one scout attack on a fighter, returns the combat text or why the attack isn't allowed
+pub fn scout_turn(
+    app: &mut App,
+    combat: &mut Combat,
+    scout_pos: usize,
+    enemy_pos: usize,
+) -> Result<String, String> {
+    // make sure valid ships are selected (not destroyed, etc.)
+    let invalid = || "Make sure a valid scout and target are selected.".to_string();
+    let scout_id = *combat.scout_formation.get(scout_pos).ok_or_else(invalid)?;
+    let enemy = combat
+        .enemy_stats
+        .get(enemy_pos)
+        .cloned()
+        .ok_or_else(invalid)?;
+    let ship = app.roster.scout(scout_id).ok_or_else(invalid)?.ship.clone();
+    let pilot = app.roster.pilot_of(scout_id).ok_or_else(invalid)?.clone();
+    let ship_ok = matches!(ship.damage, ShipDamage::Normal | ShipDamage::Half);
+    let pilot_ok = matches!(pilot.status, PilotStatus::Normal | PilotStatus::Injured);
+    let target_ok = enemy.is_active();
+    let recalled = combat.recalled.contains(&scout_id);
+    if !combat.scout_half
+        || combat.scout_turns[scout_pos]
+        || recalled
+        || !ship_ok
+        || !pilot_ok
+        || !target_ok
+    {
+        return Err(invalid());
+    }
+
+    if combat.shielded(enemy_pos) {
+        return Err("The capital ship's shields are up - bring them down first.".to_string());
+    }
+
+    let (damage, record) = scout_attack(&ship, &pilot);
+    app.current_leap.rolls.push(record);
+    // update combat log
+    app.current_leap.damage[enemy_pos] += damage;
+    // apply damage, check for kill and mark if appropriate
+    let killed = combat.enemy_stats[enemy_pos].take_damage(damage);
+    if killed {
+        if let Some(pilot) = app.roster.pilot_mut(pilot.id) {
+            pilot.mark_kill(&enemy.model);
+            pilot.rank_up();
+        }
+        app.current_leap.kills.push((pilot.id, enemy.model.clone()));
+    }
+    combat.scout_turns[scout_pos] = true;
+    let mut text = format!("{} deals {} damage to {}", pilot.name, damage, enemy.model);
+    if killed {
+        text += capital_destroyed_text(&enemy.model);
+    }
+    combat.log.push(text.clone());
+    Ok(text)
+}
+
+/// avionics it takes to recall a scout mid-fight - the scout bay has to catch it on the way in
+/// 1 part, 2 if the bay is barely functioning, each upgrade tier takes 1 off
+/// an inoperable bay can't take scouts back at all
+pub fn recall_cost(scout_bay: &SubSystem) -> Result<u64, String> {
+    let cost: u64 = match scout_bay.status {
+        Status::Normal | Status::Serviceable => 1,
+        Status::BarelyFunctioning => 2,
+        Status::Inoperable => {
+            return Err("The scout bay is inoperable - nobody can be recalled.".to_string())
+        }
+    };
+    Ok(cost.saturating_sub(u64::from(scout_bay.upgrade)))
+}
+
+/// pulls a scout out of the fight for the rest of combat, using its turn
+/// its slot is left open so enemy fire aimed there hits the colony ship hull instead
+pub fn recall_turn(app: &mut App, combat: &mut Combat, scout_pos: usize) -> Result<String, String> {
+    let invalid = || "Select a scout that hasn't acted yet to recall it.".to_string();
+    let scout_id = *combat.scout_formation.get(scout_pos).ok_or_else(invalid)?;
+    if !combat.scout_half || combat.scout_turns[scout_pos] || combat.recalled.contains(&scout_id) {
+        return Err(invalid());
+    }
+    let cost = recall_cost(&app.scout_bay)?;
+    if !app.parts.can_afford(Part::Avionics, cost) {
+        return Err(format!(
+            "Recalling a scout takes {cost} {}, you have {}.",
+            Part::Avionics,
+            app.parts
+        ));
+    }
+    app.parts.spend(Part::Avionics, cost)?;
+    combat.recalled.push(scout_id);
+    combat.scout_turns[scout_pos] = true;
+    let name = app
+        .roster
+        .scout(scout_id)
+        .map_or_else(String::new, |x| x.ship.name.clone());
+    let text = format!("{name} is recalled to the scout bay ({cost} part(s)).");
+    combat.log.push(text.clone());
+    Ok(text)
+}
+
+/// resolves a mining laser shot on a fighter, returns the combat text or why it can't fire
+pub fn laser_turn(app: &mut App, combat: &mut Combat, enemy_pos: usize) -> Result<String, String> {
+    let target_ok = combat
+        .enemy_stats
+        .get(enemy_pos)
+        .is_some_and(|x| x.is_active());
+    if combat.laser_jammed() {
+        return Err("A Jammer is scrambling the mining laser - take it out first.".to_string());
+    }
+    if !combat.scout_half || combat.laser_fired || !target_ok || combat.rounds <= 1 {
+        return Err(
+            "Mining laser available starting in round 2.  Make sure a valid target is selected."
+                .to_string(),
+        );
+    }
+    if combat.shielded(enemy_pos) {
+        return Err("The capital ship's shields are up - bring them down first.".to_string());
+    }
+    let enemy = combat.enemy_stats[enemy_pos].clone();
+    let (damage, record) = mining_laser(app.mining_laser.upgrade);
+    app.current_leap.rolls.push(record);
+    // update leap log
+    app.current_leap.damage[enemy_pos] += damage;
+    // apply damage
+    let killed = combat.enemy_stats[enemy_pos].take_damage(damage);
+    if killed {
+        app.laser_kills += combat.enemy_stats[enemy_pos].model.kill_value();
+        laser_tier_progress(app);
+    }
+    combat.laser_fired = true;
+    let mut text = format!("Mining laser deals {} damage to {}", damage, enemy.model);
+    if killed {
+        text += capital_destroyed_text(&enemy.model);
+    }
+    combat.log.push(text.clone());
+    Ok(text)
+}
+
+/// pursuit roll for an enemy that got away - one that broke off for fuel follows on a 5+,
+/// one we jumped away from still has fuel and follows on a 4+
+pub fn pursuit_roll(model: &Threats, escaped: bool) -> (bool, RollRecord) {
+    let record = RollRecord::roll(&format!("Pursuit ({model})"), 1, 6);
+    let target = if escaped { 4 } else { 5 };
+    let follows = record.total() >= target;
+    (
+        follows,
+        record.outcome(if follows { "follows" } else { "gives up" }),
+    )
+}
+
+/// rolls pursuit for every fighter that got away, the ones that follow are refueled and kept
+/// (with the hp they had left) to join the next encounter - capital ship sections never follow
+/// returns how many are now pursuing
+pub fn gather_pursuers(app: &mut App, fighters: &[Fighter], escaped: bool) -> usize {
+    let mut followed = 0;
+    for fighter in fighters.iter().filter(|x| !x.model.is_capital()) {
+        let got_away = if escaped {
+            fighter.is_active()
+        } else {
+            fighter.state == FighterState::Retreated
+        };
+        if !got_away {
+            continue;
+        }
+        let (follows, record) = pursuit_roll(&fighter.model, escaped);
+        app.current_leap.rolls.push(record);
+        if let (true, Some(fresh)) = (follows, fresh_fighter(&fighter.model)) {
+            app.pursuers.push(Fighter {
+                hp: fighter.hp,
+                ..fresh
+            });
+            followed += 1;
+        }
+    }
+    followed
+}
+
+/// round bookkeeping - hands the turn between scouts and enemies, burns enemy fuel at the end of
+/// each round and ends combat once every enemy is destroyed or out of fuel
+pub fn advance_combat(app: &mut App) {
+    if !app.in_combat {
+        return;
+    }
+    let Some(mut combat) = app.combat.clone() else {
+        return;
+    };
+
+    // check if combat is resolved
+    if combat.enemy_stats.iter().all(|x| !x.is_active()) {
+        app.in_combat = false;
+        app.wreckage = wreckage(&combat.enemy_stats); // kept for the salvage phase
+        combat.log.push("Combat over.".to_string());
+        let followed = gather_pursuers(app, &combat.enemy_stats, false);
+        if followed > 0 {
+            combat.log.push(format!(
+                "{followed} retreating fighter(s) are shadowing the colony ship."
+            ));
+        }
+    }
+
+    if combat.rounds == 1 {
+        combat.laser_fired = true;
+    }
+
+    // check to see if all of scouts have taken a turn
+    if combat.scout_turns.iter().all(|x| *x) && combat.laser_fired {
+        combat.scout_half = false; // now enemy turn
+        combat.scout_turns = vec![false; combat.scout_formation.len()]; // reset
+    }
+    if combat.enemy_turns.iter().all(|x| *x) {
+        combat.scout_half = true;
+        combat.enemy_turns = vec![false; combat.enemy_formation.len()];
+        combat.laser_fired = false;
+        // end of round, +/- fuel, round counter, etc.
+        combat.rounds += 1;
+        app.current_leap.combat_rounds += 1; // update log
+        for enemy in combat.enemy_stats.iter_mut() {
+            if enemy.burn_fuel() {
+                let text = format!("{} is out of fuel and breaks off.", enemy.model);
+                combat.combat_text = text.clone();
+                combat.log.push(text);
+            }
+        }
+        let launched = combat.launch_from_carriers();
+        if launched > 0 {
+            // the new fighters join the leap log and get their own turns from this round on
+            for _ in 0..launched {
+                app.current_leap.threats.push(Threats::Mk1);
+                app.current_leap.damage.push(0);
+            }
+            combat.enemy_turns = vec![false; combat.enemy_formation.len()];
+            let text = format!("Carrier launches {launched} MK1 fighter(s)!");
+            combat.combat_text = text.clone();
+            combat.log.push(text);
+        }
+    }
+
+    // inoperable scouts limp back to the scout bay on their own, no charge
+    for scout_id in combat.scout_formation.clone() {
+        let inoperable = app
+            .roster
+            .scout(scout_id)
+            .is_some_and(|x| x.ship.damage == ShipDamage::Inoperable);
+        if inoperable && !combat.recalled.contains(&scout_id) {
+            combat.recalled.push(scout_id);
+            combat.log.push(format!(
+                "{} is inoperable and returns to the scout bay.",
+                app.roster.scout(scout_id).map_or("Scout", |x| &x.ship.name)
+            ));
+        }
+    }
+
+    // skip turns for Scouts that are recalled, inoperable, destroyed, or KIA
+    for (i, scout_id) in combat.scout_formation.iter().enumerate() {
+        let ship_down = app.roster.scout(*scout_id).is_none_or(|x| {
+            matches!(
+                x.ship.damage,
+                ShipDamage::Inoperable | ShipDamage::Destroyed
+            )
+        });
+        let pilot_down = app
+            .roster
+            .pilot_of(*scout_id)
+            .is_none_or(|x| x.status == PilotStatus::Kia);
+        if ship_down || pilot_down || combat.recalled.contains(scout_id) {
+            combat.scout_turns[i] = true;
+        }
+    }
+
+    app.combat = Some(combat);
+}
+
+/// how the auto-resolver picks its targets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoPolicy {
+    /// scouts go in formation order at the front-most fighter
+    #[default]
+    LeadFirst,
+    /// every scout piles onto the fighter with the least hp left
+    FocusWeakest,
+}
+
+impl fmt::Display for AutoPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            AutoPolicy::LeadFirst => "Lead scout first",
+            AutoPolicy::FocusWeakest => "Focus weakest",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl AutoPolicy {
+    pub fn next(&self) -> Self {
+        match self {
+            AutoPolicy::LeadFirst => AutoPolicy::FocusWeakest,
+            AutoPolicy::FocusWeakest => AutoPolicy::LeadFirst,
+        }
+    }
+
+    /// policy by its short name, as given on the command line or by a bot
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.to_lowercase().as_str() {
+            "lead" => Ok(AutoPolicy::LeadFirst),
+            "weakest" => Ok(AutoPolicy::FocusWeakest),
+            _ => Err(format!("Unknown policy '{text}' (lead or weakest).")),
+        }
+    }
+
+    /// target for the next scout attack, None if nothing is left to shoot
+    fn scout_target(&self, combat: &Combat) -> Option<usize> {
+        let mut active = combat
+            .enemy_stats
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| x.is_active() && !combat.shielded(*i));
+        match self {
+            AutoPolicy::LeadFirst => active.next().map(|(i, _)| i),
+            AutoPolicy::FocusWeakest => active.min_by_key(|(_, x)| x.hp).map(|(i, _)| i),
+        }
+    }
+}
+
+/// mining laser always goes after the fighter with the most hp left that it can actually hit
+fn laser_target(combat: &Combat) -> Option<usize> {
+    combat
+        .enemy_stats
+        .iter()
+        .enumerate()
+        .filter(|(i, x)| x.is_active() && !combat.shielded(*i))
+        .max_by_key(|(_, x)| x.hp)
+        .map(|(i, _)| i)
+}
+
+/// launched pilots one more hit could cost - already injured, or flying a scout at half damage
+fn pilots_in_danger(app: &App, combat: &Combat) -> Vec<String> {
+    combat
+        .scout_formation
+        .iter()
+        .filter(|id| !combat.recalled.contains(id))
+        .filter_map(|id| {
+            let scout = app.roster.scout(*id)?;
+            let pilot = app.roster.pilot_of(*id)?;
+            let danger =
+                pilot.status == PilotStatus::Injured || scout.ship.damage == ShipDamage::Half;
+            danger.then(|| pilot.name.clone())
+        })
+        .collect()
+}
+
+/// pilots whose status got worse since `before` was taken
+fn pilots_at_risk(before: &BTreeMap<u64, PilotStatus>, app: &App) -> Vec<String> {
+    app.roster
+        .pilots
+        .iter()
+        .filter(|pilot| before.get(&pilot.id).is_some_and(|x| *x != pilot.status))
+        .map(|pilot| pilot.name.clone())
+        .collect()
+}
+
+/// plays the current combat out with the given policy, following the same rules as the manual
+/// keys, and returns a summary of how it ended
+/// with `hand_back` it stops before any enemy turn that could cost a pilot (one is injured or
+/// flies a half damaged scout), and after any hit a pilot takes, so the player can take over
+pub fn auto_resolve(app: &mut App, policy: AutoPolicy, hand_back: bool) -> String {
+    let before: BTreeMap<u64, PilotStatus> = app
+        .roster
+        .pilots
+        .iter()
+        .map(|x| (x.id, x.status.clone()))
+        .collect();
+    // NOTE: guards against a stuck combat, no real fight comes close to this
+    for _ in 0..1000 {
+        advance_combat(app);
+        if !app.in_combat {
+            return "Auto-resolve finished the fight.".to_string();
+        }
+        let Some(mut combat) = app.combat.clone() else {
+            break;
+        };
+        if combat.scout_half {
+            for scout_pos in 0..combat.scout_formation.len() {
+                if combat.scout_turns[scout_pos] {
+                    continue;
+                }
+                match policy.scout_target(&combat) {
+                    Some(enemy_pos) => {
+                        // a rejected attack just means this scout can't act, skip it
+                        if scout_turn(app, &mut combat, scout_pos, enemy_pos).is_err() {
+                            combat.scout_turns[scout_pos] = true;
+                        }
+                    }
+                    None => combat.scout_turns[scout_pos] = true,
+                }
+            }
+            if !combat.laser_fired {
+                let fired =
+                    laser_target(&combat).is_some_and(|x| laser_turn(app, &mut combat, x).is_ok());
+                if !fired {
+                    combat.laser_fired = true; // nothing to shoot or not in range yet
+                }
+            }
+            combat.combat_text = "Scouts have attacked.".to_string();
+        } else {
+            let danger = pilots_in_danger(app, &combat);
+            if hand_back && !danger.is_empty() {
+                return format!(
+                    "Auto-resolve stopped before the enemy turn: {} can't take another hit.  You have control.",
+                    danger.join(", ")
+                );
+            }
+            enemy_turn(&mut combat, app);
+            combat.log.push(combat.combat_text.clone());
+        }
+        app.combat = Some(combat);
+        let lost = pilots_at_risk(&before, app);
+        if hand_back && !lost.is_empty() {
+            advance_combat(app);
+            return format!(
+                "Auto-resolve stopped: {} took a hit.  You have control.",
+                lost.join(", ")
+            );
+        }
     }
+    "Auto-resolve stopped.".to_string()
 }
@@ -0,0 +1,86 @@
+use ratatui::{style::Stylize, text::Line};
+use serde::Serialize;
+
+use super::roll;
+
+/// a single modifier applied to a roll and the rule it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Modifier {
+    pub source: String,
+    pub value: i64,
+}
+
+impl Modifier {
+    pub fn new(source: &str, value: i64) -> Self {
+        Modifier {
+            source: source.to_string(),
+            value,
+        }
+    }
+}
+
+/// everything that went into a roll: raw dice, modifiers, final value and the table result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RollRecord {
+    pub label: String,
+    pub dice: Vec<i64>,
+    pub modifiers: Vec<Modifier>,
+    pub outcome: String,
+}
+
+impl RollRecord {
+    /// rolls `count` dice with `sides` sides and starts a record for them
+    pub fn roll(label: &str, count: usize, sides: i64) -> Self {
+        RollRecord {
+            label: label.to_string(),
+            dice: (0..count).map(|_| roll(sides)).collect(),
+            modifiers: Vec::new(),
+            outcome: String::new(),
+        }
+    }
+
+    /// adds a modifier to the roll, zero modifiers are skipped so the record only shows what mattered
+    pub fn modifier(&mut self, source: &str, value: i64) {
+        if value != 0 {
+            self.modifiers.push(Modifier::new(source, value));
+        }
+    }
+
+    /// sum of the raw dice before modifiers
+    pub fn raw(&self) -> i64 {
+        self.dice.iter().sum()
+    }
+
+    /// final value after all modifiers
+    pub fn total(&self) -> i64 {
+        self.raw() + self.modifiers.iter().map(|x| x.value).sum::<i64>()
+    }
+
+    /// sets the table result and hands the record back, for chaining at the end of a rule
+    pub fn outcome(mut self, outcome: &str) -> Self {
+        self.outcome = outcome.to_string();
+        self
+    }
+
+    /// one line summary for the dice panel and leap log
+    /// e.g. "Scout attack: [4] +1 (Veteran) -1 (Half damage) = 4 -> miss"
+    pub fn to_line(&self) -> Line<'_> {
+        let dice = self
+            .dice
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join("+");
+        let mut modifiers = String::new();
+        for modifier in &self.modifiers {
+            modifiers += &format!(" {:+} ({})", modifier.value, modifier.source);
+        }
+        Line::from(vec![
+            format!("{}: ", self.label).cyan(),
+            format!("[{dice}]").yellow().bold(),
+            modifiers.into(),
+            format!(" = {} ", self.total()).bold(),
+            format!("-> {}", self.outcome).green(),
+        ])
+    }
+}
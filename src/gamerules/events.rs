@@ -0,0 +1,167 @@
+use rand::seq::SliceRandom;
+use serde::Serialize;
+
+use crate::{app::App, resources::events::EVENT_DECK};
+
+use super::{dice::RollRecord, parts::Parts, pilot::Pilot, pilot::PilotStatus, rng};
+
+/// one thing a choice does to the ship, applied in order
+/// negative fuel and parts at the top level of a choice are its price and must be affordable
+/// parts are untyped here - costs take whatever is most plentiful, gains come in random kinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Effect {
+    Fuel(i64),
+    Parts(i64),
+    HullDamage(u64),
+    RecruitPilot,        // a rookie joins the crew, unassigned
+    InjurePilots(usize), // healthy pilots come down sick, up to this many
+    /// d6 roll, `success` on target or better, `failure` otherwise
+    Chance {
+        target: i64,
+        success: &'static [Effect],
+        failure: &'static [Effect],
+    },
+}
+
+/// one option on an event card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EventChoice {
+    pub label: &'static str,
+    pub effects: &'static [Effect],
+}
+
+/// an event in the deck - new events only need a new entry in resources/events.rs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EventCard {
+    pub name: &'static str,
+    pub text: &'static str,
+    pub choices: &'static [EventChoice],
+}
+
+/// an event waiting on the player's choice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PendingEvent {
+    pub card: &'static EventCard,
+    pub selected: usize,
+}
+
+impl EventChoice {
+    /// fuel and parts the choice costs up front
+    pub fn cost(&self) -> (u64, u64) {
+        let mut fuel = 0;
+        let mut parts = 0;
+        for effect in self.effects {
+            match effect {
+                Effect::Fuel(x) if *x < 0 => fuel += x.unsigned_abs(),
+                Effect::Parts(x) if *x < 0 => parts += x.unsigned_abs(),
+                _ => {}
+            }
+        }
+        (fuel, parts)
+    }
+}
+
+/// rolled after every leap, a 5+ draws a card from the event deck
+pub fn event_check() -> (Option<&'static EventCard>, RollRecord) {
+    let record = RollRecord::roll("Event check", 1, 6);
+    if record.total() >= 5 {
+        let card = EVENT_DECK.choose(&mut rng());
+        let outcome = card.map_or("nothing", |x| x.name);
+        (card, record.outcome(outcome))
+    } else {
+        (None, record.outcome("nothing"))
+    }
+}
+
+/// applies a list of effects, describing each one
+fn apply_effects(app: &mut App, effects: &[Effect], results: &mut Vec<String>) {
+    for effect in effects {
+        match effect {
+            Effect::Fuel(x) => {
+                app.fuel = app.fuel.saturating_add_signed(*x);
+                results.push(format!("{x:+} fuel"));
+            }
+            Effect::Parts(x) if *x < 0 => {
+                let _ = app.parts.spend_any(x.unsigned_abs());
+                results.push(format!("{x:+} parts"));
+            }
+            Effect::Parts(x) => {
+                let found = Parts::random(x.unsigned_abs());
+                app.parts.merge(&found);
+                results.push(format!("+{found}"));
+            }
+            Effect::HullDamage(x) => {
+                app.hull_damage += x;
+                results.push(format!("{x} hull damage"));
+            }
+            Effect::RecruitPilot => {
+                let name = app.names.pilot_name();
+                app.roster.add_pilot(Pilot {
+                    name: name.clone(),
+                    ..Pilot::default()
+                });
+                results.push(format!("{name} joins the crew"));
+            }
+            Effect::InjurePilots(count) => {
+                let mut healthy: Vec<&mut Pilot> = app
+                    .roster
+                    .pilots
+                    .iter_mut()
+                    .filter(|x| x.status == PilotStatus::Normal)
+                    .collect();
+                healthy.shuffle(&mut rng());
+                for pilot in healthy.into_iter().take(*count) {
+                    pilot.status = PilotStatus::Injured;
+                    results.push(format!("{} falls ill", pilot.name));
+                }
+            }
+            Effect::Chance {
+                target,
+                success,
+                failure,
+            } => {
+                let record = RollRecord::roll("Event", 1, 6);
+                let passed = record.total() >= *target;
+                app.current_leap.rolls.push(record.outcome(if passed {
+                    "success"
+                } else {
+                    "failure"
+                }));
+                apply_effects(app, if passed { success } else { failure }, results);
+            }
+        }
+    }
+}
+
+/// resolves the selected choice of the pending event and records it in the leap log
+pub fn resolve_event(app: &mut App) -> Result<String, String> {
+    let Some(pending) = app.event else {
+        return Err("No event to resolve.".to_string());
+    };
+    let choice = pending
+        .card
+        .choices
+        .get(pending.selected)
+        .ok_or_else(|| "Pick one of the choices.".to_string())?;
+    let (fuel, parts) = choice.cost();
+    if app.fuel < fuel || app.parts.total() < parts {
+        return Err(format!(
+            "{} needs {fuel} fuel and {parts} parts.",
+            choice.label
+        ));
+    }
+    let mut results = Vec::new();
+    apply_effects(app, choice.effects, &mut results);
+    if results.is_empty() {
+        results.push("nothing happens".to_string());
+    }
+    let text = format!(
+        "{}: {} -> {}",
+        pending.card.name,
+        choice.label,
+        results.join(", ")
+    );
+    app.current_leap.events.push(text.clone());
+    app.event = None;
+    Ok(text)
+}
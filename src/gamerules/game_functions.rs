@@ -6,27 +6,25 @@
  * 5. scan the system
  * 6. make repairs and bury your dead
  * 7. leap again
+ *
+ * the phases themselves (and what is allowed in each) live in turn.rs
  */
 
 use crate::app::App;
 
-use super::{roll, threat::Threats, ScanResult};
-
-/// enum for tracking which step of the jump the player is in
-#[derive(Debug, PartialEq)]
-pub enum JumpStep {
-    Step1,
-    Step2,
-    Step3,
-    Step4,
-    Step5,
-    Step6,
-    Step7,
-}
+use super::{
+    dice::RollRecord,
+    rules::Rules,
+    sector::Destination,
+    ship::{Status, SubSystem},
+    threat::{boss_group, Threats, Wreck},
+    ScanResult,
+};
 
 /// Step 1. leap into system
 pub fn leap_into_system(app: &mut App) {
-    app.leaps_since_incident += 1;
+    app.leaps_since_incident = next_leaps_since_incident(app);
+    app.incident = false;
     if app.fuel > 0 {
         app.fuel -= 1;
     } else {
@@ -34,22 +32,53 @@ pub fn leap_into_system(app: &mut App) {
     }
 }
 
-/// Step 2. assess threat
-pub fn assess_threat(app: &App) -> Option<Vec<Threats>> {
-    let roll_mod: i64 = if app.leaps_since_incident == 1 {
+/// leaps since incident once the next leap is made
+/// a fight seen through to the end starts the count over, one we jumped away from doesn't
+pub fn next_leaps_since_incident(app: &App) -> u64 {
+    if app.incident {
+        1
+    } else {
+        app.leaps_since_incident + 1
+    }
+}
+
+/// extra fuel burned by an emergency jump out of combat, on top of the leap's usual 1
+/// a healthy engine spools up for 1 extra, each step of damage adds 1, each upgrade tier takes
+/// 1 off, and an inoperable engine can't jump at all
+pub fn emergency_jump_cost(engine: &SubSystem) -> Result<u64, String> {
+    let extra: u64 = match engine.status {
+        Status::Normal => 1,
+        Status::Serviceable => 2,
+        Status::BarelyFunctioning => 3,
+        Status::Inoperable => {
+            return Err("The engines are inoperable - no emergency jump possible.".to_string())
+        }
+    };
+    Ok(extra.saturating_sub(u64::from(engine.upgrade)))
+}
+
+/// modifier to the threat roll from leaps since the last incident
+pub fn threat_modifier(leaps: u64) -> i64 {
+    if leaps == 1 {
         -3
-    } else if app.leaps_since_incident == 2 {
+    } else if leaps == 2 {
         -2
-    } else if app.leaps_since_incident == 3 {
+    } else if leaps == 3 {
         -1
-    } else if app.leaps_since_incident >= 4 && app.leaps_since_incident <= 7 {
+    } else if (4..=7).contains(&leaps) {
         0
     } else {
-        app.leaps_since_incident as i64 - 7
-    };
+        leaps as i64 - 7
+    }
+}
 
-    let threat_result = roll(6) + roll(6) + roll_mod;
+/// modifier to the threat roll from enemies following us - each one makes trouble likelier
+pub fn pursuit_modifier(pursuers: usize) -> i64 {
+    pursuers as i64
+}
 
+/// threat table - enemy group for a modified 2d6 threat roll, None means the sector is clear
+pub fn threat_table(threat_result: i64) -> Option<Vec<Threats>> {
     if threat_result <= 3 {
         None
     } else if threat_result == 4 {
@@ -78,40 +107,152 @@ pub fn assess_threat(app: &App) -> Option<Vec<Threats>> {
         Some(threat)
     } else if threat_result == 14 {
         Some(vec![Threats::Mk3, Threats::Mk2])
-    } else {
+    } else if threat_result == 15 {
         let mut threat = vec![Threats::Mk3; 2];
         threat.append(&mut vec![Threats::Mk2; 2]);
         threat.append(&mut vec![Threats::Mk1; 2]);
         Some(threat)
+    } else if threat_result == 16 {
+        Some(vec![
+            Threats::Bomber,
+            Threats::Mk2,
+            Threats::Mk1,
+            Threats::Mk1,
+        ])
+    } else if threat_result == 17 {
+        Some(vec![Threats::Interceptor, Threats::Mk3, Threats::Mk2])
+    } else if threat_result == 18 {
+        Some(vec![
+            Threats::Jammer,
+            Threats::Mk3,
+            Threats::Mk2,
+            Threats::Mk1,
+        ])
+    } else {
+        Some(vec![Threats::Carrier, Threats::Bomber, Threats::Mk2])
+    }
+}
+
+/// enemy group for a modified threat roll on the given leap of the campaign
+/// until it's destroyed the capital ship turns up on the boss leap itself, or on any leap with a
+/// roll of the boss threshold or more - a streak only brings it once, an escape doesn't bring it back
+pub fn threat_group(
+    rules: &Rules,
+    boss_defeated: bool,
+    leap: u64,
+    threat_result: i64,
+) -> Option<Vec<Threats>> {
+    let boss_due = leap == rules.boss_after_leaps || threat_result >= rules.boss_threshold;
+    if boss_due && !boss_defeated {
+        Some(boss_group())
+    } else {
+        threat_table(threat_result)
+    }
+}
+
+/// every modifier on a threat roll made at `leaps` since incident in `system` (None if it isn't
+/// known), with where each one comes from - the roll itself and the forecast both use these
+pub fn threat_roll_modifiers(
+    app: &App,
+    leaps: u64,
+    system: Option<&Destination>,
+) -> Vec<(String, i64)> {
+    let mut modifiers = vec![
+        (
+            format!("{leaps} leaps since incident"),
+            threat_modifier(leaps),
+        ),
+        (
+            format!("{} pursuers", app.pursuers.len()),
+            pursuit_modifier(app.pursuers.len()),
+        ),
+    ];
+    if let Some(system) = system {
+        modifiers.push((system.name.clone(), system.threat_modifier));
+    }
+    modifiers.push(("Difficulty".to_string(), app.rules.threat_modifier));
+    modifiers
+}
+
+/// Step 2. assess threat
+pub fn assess_threat(app: &App) -> (Option<Vec<Threats>>, RollRecord) {
+    let mut record = RollRecord::roll("Threat assessment", 2, 6);
+    let system = app.sector.current.as_ref();
+    for (source, value) in threat_roll_modifiers(app, app.leaps_since_incident, system) {
+        record.modifier(&source, value);
+    }
+    let threats = threat_group(
+        &app.rules,
+        app.boss_defeated,
+        app.current_leap.number,
+        record.total(),
+    );
+    let outcome = match &threats {
+        Some(threats) => threats_summary(threats),
+        None => "sector clear".to_string(),
+    };
+    (threats, record.outcome(&outcome))
+}
+
+/// short description of an enemy group for roll records, e.g. "1 MK2, 3 MK1"
+pub fn threats_summary(threats: &[Threats]) -> String {
+    let mut counts: Vec<(Threats, usize)> = Vec::new();
+    for threat in threats {
+        match counts.iter_mut().find(|(model, _)| model == threat) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((threat.clone(), 1)),
+        }
     }
+    counts
+        .iter()
+        .map(|(model, count)| format!("{count} {model}"))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
-/// Step 4. search wreckage for parts
-pub fn search_wreckage(threats: &[Threats]) -> u64 {
+/// Step 4. search wreckage for parts - only destroyed fighters leave anything to search
+pub fn search_wreckage(wreckage: &[Wreck]) -> (u64, RollRecord) {
     let mut bmk2 = false;
     let mut bmk3 = false;
-    for threat in threats {
-        if *threat == Threats::Mk2 {
+    for wreck in wreckage.iter().filter(|x| x.destroyed) {
+        if wreck.model == Threats::Mk2 {
             bmk2 = true;
-        } else if *threat == Threats::Mk3 {
+        } else if wreck.model == Threats::Mk3 {
             bmk3 = true;
         }
     }
-    let roll_mod = if bmk3 && bmk2 {
-        3
+    let mut record = RollRecord::roll("Search wreckage", 1, 6);
+    if bmk3 && bmk2 {
+        record.modifier("MK3 and MK2 wreckage", 3);
     } else if bmk3 {
-        2
+        record.modifier("MK3 wreckage", 2);
     } else if bmk2 {
-        1
-    } else {
-        0
-    };
-    roll(6) as u64 + roll_mod
+        record.modifier("MK2 wreckage", 1);
+    }
+    // the new archetypes each carry something worth pulling out, once per type
+    let destroyed = |model: Threats| wreckage.iter().any(|x| x.destroyed && x.model == model);
+    if destroyed(Threats::Carrier) {
+        record.modifier("Carrier wreckage", 3);
+    }
+    if destroyed(Threats::Bomber) {
+        record.modifier("Bomber wreckage", 1);
+    }
+    if destroyed(Threats::Interceptor) {
+        record.modifier("Interceptor wreckage", 1);
+    }
+    if destroyed(Threats::Jammer) {
+        record.modifier("Jammer wreckage", 1);
+    }
+    if wreckage.iter().any(|x| x.destroyed && x.model.is_capital()) {
+        record.modifier("Capital ship wreckage", 6);
+    }
+    let parts = record.total().max(0) as u64;
+    (parts, record.outcome(&format!("{parts} parts")))
 }
 
-/// Step 5. scan the system
-pub fn system_scan(leaps: u64) -> (u64, ScanResult) {
-    let roll_mod = if leaps == 1 {
+/// modifier to the scan roll from leaps since the last incident
+pub fn scan_modifier(leaps: u64) -> i64 {
+    if leaps == 1 {
         -3
     } else if leaps == 2 {
         -2
@@ -121,9 +262,11 @@ pub fn system_scan(leaps: u64) -> (u64, ScanResult) {
         0
     } else {
         1
-    };
+    }
+}
 
-    let system_scan = roll(6) + roll(6) + roll_mod;
+/// scan table - fuel gathered and scan result for a modified 2d6 scan roll
+pub fn scan_table(system_scan: i64) -> (u64, ScanResult) {
     if system_scan < 6 {
         (0, ScanResult::Barren)
     } else if system_scan == 6 || system_scan == 8 {
@@ -139,3 +282,19 @@ pub fn system_scan(leaps: u64) -> (u64, ScanResult) {
         (0, ScanResult::Home)
     }
 }
+
+/// Step 5. scan the system
+pub fn system_scan(app: &App) -> (u64, ScanResult, RollRecord) {
+    let leaps = app.leaps_since_incident;
+    let mut record = RollRecord::roll("System scan", 2, 6);
+    record.modifier(
+        &format!("{leaps} leaps since incident"),
+        scan_modifier(leaps),
+    );
+    if let Some(system) = &app.sector.current {
+        record.modifier(&system.name, system.scan_modifier);
+    }
+    let (fuel, result) = scan_table(record.total());
+    let outcome = format!("{result} ({fuel} fuel)");
+    (fuel, result, record.outcome(&outcome))
+}
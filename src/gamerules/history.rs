@@ -0,0 +1,112 @@
+use crate::app::App;
+
+use super::{
+    outpost::Outpost, parts::Parts, rolls_made, roster::Roster, ship::SubSystem, turn::Phase,
+};
+
+/// everything a decision without dice can change, plus when it was taken
+/// undo steps are only good while the phase and the dice rolled stay the same
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    epoch: (Phase, u64),
+    fuel: u64,
+    parts: Parts,
+    hull_damage: u64,
+    hull_upgrade: u8,
+    subsystems: [SubSystem; 5],
+    roster: Roster,
+    outpost: Option<Outpost>,
+}
+
+impl Snapshot {
+    pub fn take(app: &App) -> Self {
+        Snapshot {
+            epoch: (app.phase, rolls_made()),
+            fuel: app.fuel,
+            parts: app.parts.clone(),
+            hull_damage: app.hull_damage,
+            hull_upgrade: app.hull_upgrade,
+            subsystems: [
+                app.engine.clone(),
+                app.mining_laser.clone(),
+                app.scout_bay.clone(),
+                app.sick_bay.clone(),
+                app.sensors.clone(),
+            ],
+            roster: app.roster.clone(),
+            outpost: app.sector.current.as_ref().and_then(|x| x.outpost.clone()),
+        }
+    }
+
+    pub fn restore(self, app: &mut App) {
+        let [engine, mining_laser, scout_bay, sick_bay, sensors] = self.subsystems;
+        app.fuel = self.fuel;
+        app.parts = self.parts;
+        app.hull_damage = self.hull_damage;
+        app.hull_upgrade = self.hull_upgrade;
+        app.engine = engine;
+        app.mining_laser = mining_laser;
+        app.scout_bay = scout_bay;
+        app.sick_bay = sick_bay;
+        app.sensors = sensors;
+        app.roster = self.roster;
+        if let Some(system) = app.sector.current.as_mut() {
+            system.outpost = self.outpost;
+        }
+    }
+}
+
+/// undo and redo stacks of decisions taken since the last roll or phase change
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    /// files away the state from before a key press
+    /// a decision that changed something becomes an undo step, anything else that changed the
+    /// game (a roll, a new phase, an event) wipes the history so it can't be taken back
+    pub fn settle(&mut self, before: Snapshot, after: &Snapshot, decision: bool) {
+        if before == *after {
+            return;
+        }
+        if decision && before.epoch == after.epoch {
+            self.undo.push(before);
+        } else {
+            self.undo.clear();
+        }
+        self.redo.clear();
+    }
+
+    /// number of steps that can be undone and redone
+    pub fn steps(&self) -> (usize, usize) {
+        (self.undo.len(), self.redo.len())
+    }
+}
+
+/// takes back the last decision, never touching anything the dice decided
+pub fn undo(app: &mut App) -> Result<String, String> {
+    let Some(snapshot) = app.history.undo.pop() else {
+        return Err("Nothing to undo since the last roll.".to_string());
+    };
+    app.history.redo.push(Snapshot::take(app));
+    snapshot.restore(app);
+    Ok(format!(
+        "Undone.  {} more step(s) to undo.",
+        app.history.undo.len()
+    ))
+}
+
+/// puts back the last decision that was undone
+pub fn redo(app: &mut App) -> Result<String, String> {
+    let Some(snapshot) = app.history.redo.pop() else {
+        return Err("Nothing to redo.".to_string());
+    };
+    app.history.undo.push(Snapshot::take(app));
+    snapshot.restore(app);
+    Ok(format!(
+        "Redone.  {} more step(s) to redo.",
+        app.history.redo.len()
+    ))
+}
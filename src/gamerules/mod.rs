@@ -1,17 +1,35 @@
 pub mod combat;
+pub mod dice;
+pub mod events;
 pub mod game_functions;
+pub mod history;
+pub mod odds;
+pub mod outpost;
+pub mod parts;
 pub mod pilot;
+pub mod planner;
+pub mod roster;
+pub mod rules;
 pub mod scout;
+pub mod sector;
 pub mod ship;
 pub mod threat;
+pub mod turn;
 
 use core::fmt;
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use rand::Rng;
+use dice::RollRecord;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
 use ratatui::{style::Stylize, text::Line};
+use roster::Roster;
+use serde::Serialize;
 use threat::Threats;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ScanResult {
     Barren,
     Fuel,
@@ -31,7 +49,7 @@ impl fmt::Display for ScanResult {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Leap {
     pub number: u64,
     pub combat_rounds: u64,
@@ -39,6 +57,9 @@ pub struct Leap {
     pub fuel_found: u64,
     pub threats: Vec<Threats>,
     pub damage: Vec<u64>,
+    pub rolls: Vec<RollRecord>,
+    pub kills: Vec<(u64, Threats)>, // pilot id and what they shot down
+    pub events: Vec<String>,        // events drawn and how they were resolved
 }
 
 impl Default for Leap {
@@ -50,27 +71,97 @@ impl Default for Leap {
             fuel_found: 0,
             threats: vec![Threats::None],
             damage: vec![0],
+            rolls: Vec::new(),
+            kills: Vec::new(),
+            events: Vec::new(),
         }
     }
 }
 
 impl Leap {
-    pub fn to_lines(self: &Self) -> Vec<Line> {
-        vec![
+    pub fn to_lines(&self, roster: &Roster) -> Vec<Line<'_>> {
+        let mut lines = vec![
             Line::from(format!("LEAP: {}", self.number)).bold().cyan(),
             format!("Combat Rounds: {}", self.combat_rounds).into(),
             format!("Parts Found: {}", self.parts_found).into(),
             format!("Fuel Found: {}", self.fuel_found).into(),
             format!("Threats: {:?}", self.threats).into(),
             format!("Damage: {:?}", self.damage).into(),
-        ]
+        ];
+        for event in &self.events {
+            lines.push(format!("Event: {event}").into());
+        }
+        for (pilot_id, model) in &self.kills {
+            lines.push(format!("Kill: {} downed a {}", roster.pilot_name(*pilot_id), model).into());
+        }
+        if !self.rolls.is_empty() {
+            lines.push("Rolls:".into());
+            lines.extend(self.rolls.iter().map(|x| x.to_line()));
+        }
+        lines
+    }
+}
+
+thread_local! {
+    /// the campaign's random numbers, everything the rules leave to chance draws from this
+    static GAME_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+    /// names get their own stream so a different names file can't change how the dice fall
+    static NAME_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// restarts both random streams from a seed - the same seed and commands replay the same game
+pub fn seed_rng(seed: u64) {
+    GAME_RNG.with(|x| *x.borrow_mut() = StdRng::seed_from_u64(seed));
+    NAME_RNG.with(|x| *x.borrow_mut() = StdRng::seed_from_u64(seed.rotate_left(32)));
+}
+
+/// handle to one of the seeded streams, use it wherever `rand::thread_rng()` would go
+/// each draw borrows the stream only for that draw, so handles can be nested freely
+#[derive(Debug, Clone, Copy)]
+pub struct GameRng(&'static std::thread::LocalKey<RefCell<StdRng>>);
+
+/// the stream for dice and everything else the rules decide at random
+pub fn rng() -> GameRng {
+    GameRng(&GAME_RNG)
+}
+
+/// the stream for generated names
+pub fn name_rng() -> GameRng {
+    GameRng(&NAME_RNG)
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.with(|x| x.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.with(|x| x.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.with(|x| x.borrow_mut().fill_bytes(dest));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.with(|x| x.borrow_mut().try_fill_bytes(dest))
     }
 }
 
+/// dice rolled so far this session, undo history is only good until the next one
+static ROLLS_MADE: AtomicU64 = AtomicU64::new(0);
+
+/// how many dice have been rolled so far
+pub fn rolls_made() -> u64 {
+    ROLLS_MADE.load(Ordering::Relaxed)
+}
+
+/// rolls a single die with the given number of sides (1..=side)
 pub fn roll(side: i64) -> i64 {
-    if side == 1 {
+    ROLLS_MADE.fetch_add(1, Ordering::Relaxed);
+    if side <= 1 {
         1
     } else {
-        rand::thread_rng().gen_range(1..side)
+        rng().gen_range(1..=side)
     }
 }
@@ -0,0 +1,161 @@
+use core::fmt;
+
+use super::{
+    combat::{attack_damage, attack_modifiers, laser_damage},
+    game_functions::{scan_modifier, scan_table},
+    pilot::Pilot,
+    ship::{Ship, Status, SubSystem},
+    threat::Threats,
+    ScanResult,
+};
+
+/// broad size of an encounter, used when the sensors can't make out the exact group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncounterClass {
+    Clear,
+    Mk1Swarm,
+    Mk2Group,
+    Mk3Group,
+    Special, // bombers, carriers, interceptors or jammers
+    Capital,
+}
+
+impl fmt::Display for EncounterClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            EncounterClass::Clear => "No contact",
+            EncounterClass::Mk1Swarm => "MK1 swarm",
+            EncounterClass::Mk2Group => "MK2 group",
+            EncounterClass::Mk3Group => "MK3 group",
+            EncounterClass::Special => "Special contacts",
+            EncounterClass::Capital => "Capital ship",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+/// sorts an enemy group by the heaviest fighter in it
+pub fn encounter_class(threats: &Option<Vec<Threats>>) -> EncounterClass {
+    match threats {
+        None => EncounterClass::Clear,
+        Some(v) if v.iter().any(|x| x.is_capital()) => EncounterClass::Capital,
+        Some(v) if v.iter().any(|x| x.is_special()) => EncounterClass::Special,
+        Some(v) if v.contains(&Threats::Mk3) => EncounterClass::Mk3Group,
+        Some(v) if v.contains(&Threats::Mk2) => EncounterClass::Mk2Group,
+        Some(_) => EncounterClass::Mk1Swarm,
+    }
+}
+
+/// chance of dealing 0, 1 or 2 damage with a scout attack
+/// uses the same modifiers and table as `scout_attack`, one d6 with every face equally likely
+pub fn attack_odds(ship: &Ship, pilot: &Pilot) -> [f64; 3] {
+    let modifier: i64 = attack_modifiers(ship, pilot).iter().map(|x| x.value).sum();
+    let mut odds = [0.0; 3];
+    for face in 1..=6 {
+        let damage = attack_damage(face + modifier, &pilot.status) as usize;
+        odds[damage.min(2)] += 1.0 / 6.0;
+    }
+    odds
+}
+
+/// chance that a single scout attack destroys a fighter with the given hp
+pub fn kill_chance(ship: &Ship, pilot: &Pilot, hp: u64) -> f64 {
+    if hp == 0 {
+        return 0.0;
+    }
+    attack_odds(ship, pilot)
+        .iter()
+        .enumerate()
+        .filter(|(damage, _)| *damage as u64 >= hp)
+        .map(|(_, chance)| chance)
+        .sum()
+}
+
+/// expected mining laser damage for one shot at this upgrade tier
+pub fn laser_expected_damage(tier: u8) -> f64 {
+    let modifier = tier as i64;
+    (1..=6)
+        .map(|face| laser_damage(face + modifier) as f64 / 6.0)
+        .sum()
+}
+
+/// formats a probability as a whole percentage for the TUI
+pub fn percent(chance: f64) -> String {
+    format!("{:.0}%", chance * 100.0)
+}
+
+/// every result of a modified 2d6 roll with its chance, lowest result first
+fn two_dice(modifier: i64) -> Vec<(i64, f64)> {
+    (2..=12)
+        .map(|sum: i64| (sum + modifier, (6 - (sum - 7).abs()) as f64 / 36.0))
+        .collect()
+}
+
+/// adds a chance to an outcome in a distribution, keeping the first-seen order
+fn accumulate<T: PartialEq>(distribution: &mut Vec<(T, f64)>, outcome: T, chance: f64) {
+    match distribution.iter_mut().find(|(x, _)| *x == outcome) {
+        Some((_, total)) => *total += chance,
+        None => distribution.push((outcome, chance)),
+    }
+}
+
+/// chance of each enemy group on the threat roll with the given total modifier, as summed from
+/// `threat_roll_modifiers`
+/// `group` turns a modified roll into the enemy group, like `threat_group` does for the real roll
+pub fn threat_forecast(
+    modifier: i64,
+    group: impl Fn(i64) -> Option<Vec<Threats>>,
+) -> Vec<(Option<Vec<Threats>>, f64)> {
+    let mut distribution = Vec::new();
+    for (result, chance) in two_dice(modifier) {
+        accumulate(&mut distribution, group(result), chance);
+    }
+    distribution
+}
+
+/// chance of each encounter class on the threat roll
+pub fn encounter_forecast(
+    modifier: i64,
+    group: impl Fn(i64) -> Option<Vec<Threats>>,
+) -> Vec<(EncounterClass, f64)> {
+    let mut distribution = vec![
+        (EncounterClass::Clear, 0.0),
+        (EncounterClass::Mk1Swarm, 0.0),
+        (EncounterClass::Mk2Group, 0.0),
+        (EncounterClass::Mk3Group, 0.0),
+        (EncounterClass::Special, 0.0),
+        (EncounterClass::Capital, 0.0),
+    ];
+    for (threats, chance) in threat_forecast(modifier, group) {
+        accumulate(&mut distribution, encounter_class(&threats), chance);
+    }
+    distribution
+}
+
+/// chance of each scan result (and the fuel it gives) on the scan roll
+/// `modifier` is anything already known on top of the leaps modifier, like the system's readings
+pub fn scan_forecast(leaps: u64, modifier: i64) -> Vec<((ScanResult, u64), f64)> {
+    let mut distribution = Vec::new();
+    for (result, chance) in two_dice(scan_modifier(leaps) + modifier) {
+        let (fuel, scan) = scan_table(result);
+        accumulate(&mut distribution, (scan, fuel), chance);
+    }
+    distribution
+}
+
+/// how much of the forecast the sensors can show
+/// 0 - nothing, 1 - contact or clear, 2 - encounter classes, 3 - scan results, 4 - exact groups
+/// each upgrade tier adds one level as long as the sensors work at all, up to exact groups
+pub fn forecast_detail(sensors: &SubSystem) -> u8 {
+    let detail = match sensors.status {
+        Status::Normal => 3,
+        Status::Serviceable => 2,
+        Status::BarelyFunctioning => 1,
+        Status::Inoperable => 0,
+    };
+    if detail > 0 {
+        (detail + sensors.upgrade).min(4)
+    } else {
+        detail
+    }
+}
@@ -0,0 +1,162 @@
+use core::fmt;
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::app::App;
+
+use super::{
+    pilot::{Pilot, Rank},
+    rng,
+    ship::{Status, SubSystem},
+};
+
+/// subsystems an outpost can overhaul, in the Status tab's order (the hull is repaired separately)
+const OVERHAULS: [&str; 5] = [
+    "Engines",
+    "Mining Laser",
+    "Scout Bay",
+    "Sick Bay",
+    "Sensors",
+];
+
+/// a trading post in a system - what it has left to sell and what it charges
+/// prices are in parts of any kind, the outpost takes whatever we have most of
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Outpost {
+    pub fuel_stock: u64,
+    pub fuel_price: u64,
+    pub pilots: u64, // veterans looking for a berth
+    pub pilot_price: u64,
+    pub repairs: u64, // jobs the dock crews can take on before they're booked up
+    pub repair_price: u64,
+}
+
+impl Outpost {
+    /// rolls an outpost's stock and prices - well stocked outposts tend to charge less
+    pub fn generate() -> Self {
+        let mut rng = rng();
+        let fuel_stock = rng.gen_range(1..=4);
+        Outpost {
+            fuel_stock,
+            fuel_price: if fuel_stock >= 3 { 2 } else { 3 },
+            pilots: rng.gen_range(0..=2),
+            pilot_price: rng.gen_range(4..=6),
+            repairs: rng.gen_range(1..=2),
+            repair_price: rng.gen_range(2..=4),
+        }
+    }
+}
+
+/// something an outpost can do for us
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Trade {
+    Fuel,
+    HirePilot,
+    HullRepair,
+    Overhaul(usize), // index into OVERHAULS
+}
+
+impl fmt::Display for Trade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Trade::Fuel => write!(f, "Buy 1 fuel"),
+            Trade::HirePilot => write!(f, "Hire a veteran pilot"),
+            Trade::HullRepair => write!(f, "Patch 1 hull damage"),
+            Trade::Overhaul(i) => write!(f, "Overhaul the {}", OVERHAULS[i]),
+        }
+    }
+}
+
+/// subsystem the overhaul at this index works on
+fn overhaul_target(app: &mut App, index: usize) -> &mut SubSystem {
+    match index {
+        0 => &mut app.engine,
+        1 => &mut app.mining_laser,
+        2 => &mut app.scout_bay,
+        3 => &mut app.sick_bay,
+        _ => &mut app.sensors,
+    }
+}
+
+/// everything the outpost in the current system offers right now, with its price in parts
+/// repairs are only offered for damage the ship actually has
+pub fn offers(app: &App) -> Vec<(Trade, u64)> {
+    let Some(outpost) = app.sector.current.as_ref().and_then(|x| x.outpost.as_ref()) else {
+        return Vec::new();
+    };
+    let mut offers = Vec::new();
+    if outpost.fuel_stock > 0 {
+        offers.push((Trade::Fuel, outpost.fuel_price));
+    }
+    if outpost.pilots > 0 {
+        offers.push((Trade::HirePilot, outpost.pilot_price));
+    }
+    if outpost.repairs > 0 {
+        if app.hull_damage > 0 {
+            // patching the hull is quick work, half the going rate
+            offers.push((Trade::HullRepair, outpost.repair_price.div_ceil(2)));
+        }
+        let systems = [
+            &app.engine,
+            &app.mining_laser,
+            &app.scout_bay,
+            &app.sick_bay,
+            &app.sensors,
+        ];
+        for (i, system) in systems.iter().enumerate() {
+            if system.status != Status::Normal {
+                offers.push((Trade::Overhaul(i), outpost.repair_price));
+            }
+        }
+    }
+    offers
+}
+
+/// makes the trade at this index of `offers`, paying for it in parts
+pub fn trade(app: &mut App, index: usize) -> Result<String, String> {
+    let (trade, price) = offers(app)
+        .get(index)
+        .copied()
+        .ok_or_else(|| "Nothing to trade here.".to_string())?;
+    if app.parts.total() < price {
+        return Err(format!(
+            "{trade} costs {price} parts, you only have {}.",
+            app.parts.total()
+        ));
+    }
+    let Some(outpost) = app.sector.current.as_mut().and_then(|x| x.outpost.as_mut()) else {
+        return Err("There's no outpost in this system.".to_string());
+    };
+    match trade {
+        Trade::Fuel => outpost.fuel_stock -= 1,
+        Trade::HirePilot => outpost.pilots -= 1,
+        Trade::HullRepair | Trade::Overhaul(_) => outpost.repairs -= 1,
+    }
+    let text = match trade {
+        Trade::Fuel => {
+            app.fuel += 1;
+            "Bought 1 fuel".to_string()
+        }
+        Trade::HirePilot => {
+            let name = app.names.pilot_name();
+            app.roster.add_pilot(Pilot {
+                name: name.clone(),
+                kills: 3,
+                rank: Rank::Veteran,
+                ..Pilot::default()
+            });
+            format!("{name} signs on")
+        }
+        Trade::HullRepair => {
+            app.hull_damage -= 1;
+            "Dock crews patch the hull".to_string()
+        }
+        Trade::Overhaul(i) => {
+            overhaul_target(app, i).status = Status::Normal;
+            format!("Dock crews overhaul the {}", OVERHAULS[i])
+        }
+    };
+    app.parts.spend_any(price)?;
+    Ok(format!("{text} for {price} parts."))
+}
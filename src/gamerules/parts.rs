@@ -0,0 +1,221 @@
+use core::fmt;
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::{Serialize, Serializer};
+
+use super::{
+    rng,
+    threat::{Threats, Wreck},
+};
+
+/// any other part can stand in for a missing one, but it takes this many to do the job
+pub const CONVERSION_RATE: u64 = 2;
+
+/// kinds of component salvaged from wrecks and spent on repairs and upgrades
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Part {
+    Plating,  // hull
+    Coils,    // engines
+    Optics,   // mining laser and sensors
+    Avionics, // scout bay and the scouts themselves
+    Medical,  // sick bay
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Part::Plating => "Hull Plating",
+            Part::Coils => "Engine Coils",
+            Part::Optics => "Optics",
+            Part::Avionics => "Avionics",
+            Part::Medical => "Medical Supplies",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl Part {
+    /// every kind of part, in inventory order
+    pub const ALL: [Part; 5] = [
+        Part::Plating,
+        Part::Coils,
+        Part::Optics,
+        Part::Avionics,
+        Part::Medical,
+    ];
+
+    /// part that repairs and upgrades the subsystem at this Status tab index
+    /// 0 hull, 1 engines, 2 mining laser, 3 scout bay, 4 sick bay, 5 sensors
+    pub fn for_subsystem(subsystem: usize) -> Part {
+        match subsystem {
+            0 => Part::Plating,
+            1 => Part::Coils,
+            2 | 5 => Part::Optics,
+            3 => Part::Avionics,
+            _ => Part::Medical,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// relative odds of each part (in `Part::ALL` order) turning up in this kind of wreck
+fn salvage_weights(model: &Threats) -> [u32; 5] {
+    match model {
+        Threats::None => [1, 1, 1, 1, 1],
+        Threats::Mk1 => [4, 2, 1, 2, 1],
+        Threats::Mk2 => [3, 3, 2, 2, 1],
+        Threats::Mk3 => [2, 3, 3, 3, 1],
+        Threats::Bomber => [5, 2, 1, 1, 1],
+        Threats::Carrier => [2, 2, 1, 4, 3],
+        Threats::Interceptor => [1, 5, 2, 2, 1],
+        Threats::Jammer => [1, 1, 4, 4, 1],
+        Threats::Capital(_) => [3, 3, 3, 3, 3],
+    }
+}
+
+/// the ship's stock of parts, by kind
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parts {
+    counts: [u64; 5],
+}
+
+impl fmt::Display for Parts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stocked = Part::ALL
+            .iter()
+            .filter(|x| self.count(**x) > 0)
+            .map(|x| format!("{} {x}", self.count(*x)))
+            .collect::<Vec<String>>();
+        if stocked.is_empty() {
+            write!(f, "nothing")
+        } else {
+            write!(f, "{}", stocked.join(", "))
+        }
+    }
+}
+
+/// serialised as a count for each kind of part
+impl Serialize for Parts {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(Part::ALL.iter().map(|x| (x, self.count(*x))))
+    }
+}
+
+impl Parts {
+    /// the same number of every kind of part
+    pub fn even(count: u64) -> Self {
+        Parts { counts: [count; 5] }
+    }
+
+    pub fn count(&self, part: Part) -> u64 {
+        self.counts[part.index()]
+    }
+
+    /// every part on board, whatever the kind
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn add(&mut self, part: Part, count: u64) {
+        self.counts[part.index()] += count;
+    }
+
+    /// adds everything in another inventory to this one
+    pub fn merge(&mut self, other: &Parts) {
+        for part in Part::ALL {
+            self.add(part, other.count(part));
+        }
+    }
+
+    /// other parts it would take to make up for a shortage of this one
+    fn substitutes_needed(&self, part: Part, count: u64) -> u64 {
+        count.saturating_sub(self.count(part)) * CONVERSION_RATE
+    }
+
+    /// enough of this part on hand, or enough of the rest to convert
+    pub fn can_afford(&self, part: Part, count: u64) -> bool {
+        self.substitutes_needed(part, count) <= self.total() - self.count(part)
+    }
+
+    /// takes `count` of a part, converting others at `CONVERSION_RATE` to cover any shortage
+    /// the most plentiful parts are converted first - returns what was converted, if anything
+    pub fn spend(&mut self, part: Part, count: u64) -> Result<Option<String>, String> {
+        if !self.can_afford(part, count) {
+            return Err(format!(
+                "Needs {count} {part} (or {CONVERSION_RATE} of any other part for each one short), you have {}.",
+                self
+            ));
+        }
+        let mut needed = self.substitutes_needed(part, count);
+        let on_hand = count.min(self.count(part));
+        self.counts[part.index()] -= on_hand;
+        if needed == 0 {
+            return Ok(None);
+        }
+        let converted = needed;
+        while needed > 0 {
+            let Some(richest) = Part::ALL
+                .into_iter()
+                .filter(|x| *x != part)
+                .max_by_key(|x| self.count(*x))
+            else {
+                break;
+            };
+            self.counts[richest.index()] -= 1;
+            needed -= 1;
+        }
+        Ok(Some(format!(
+            "converted {converted} other parts for {} {part}",
+            count - on_hand
+        )))
+    }
+
+    /// takes `count` parts of any kind, whichever are most plentiful - for costs that don't care
+    /// what they're paid in
+    pub fn spend_any(&mut self, count: u64) -> Result<(), String> {
+        if self.total() < count {
+            return Err(format!(
+                "Needs {count} parts, you only have {}.",
+                self.total()
+            ));
+        }
+        for _ in 0..count {
+            if let Some(richest) = Part::ALL.into_iter().max_by_key(|x| self.count(*x)) {
+                self.counts[richest.index()] -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// `count` parts of random kinds, each as likely as the next
+    pub fn random(count: u64) -> Parts {
+        let mut rng = rng();
+        let mut parts = Parts::default();
+        for _ in 0..count {
+            parts.add(Part::ALL[rng.gen_range(0..Part::ALL.len())], 1);
+        }
+        parts
+    }
+}
+
+/// sorts `count` salvaged parts into kinds - each comes out of a random destroyed wreck and is
+/// drawn from what that kind of ship carries
+pub fn sort_salvage(wreckage: &[Wreck], count: u64) -> Parts {
+    let hulks: Vec<&Wreck> = wreckage.iter().filter(|x| x.destroyed).collect();
+    if hulks.is_empty() {
+        return Parts::random(count);
+    }
+    let mut rng = rng();
+    let mut parts = Parts::default();
+    for _ in 0..count {
+        let hulk = hulks[rng.gen_range(0..hulks.len())];
+        let Ok(dist) = WeightedIndex::new(salvage_weights(&hulk.model)) else {
+            continue;
+        };
+        parts.add(Part::ALL[dist.sample(&mut rng)], 1);
+    }
+    parts
+}
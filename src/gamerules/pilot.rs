@@ -1,8 +1,10 @@
 use core::fmt;
 
+use serde::Serialize;
+
 use super::threat::Threats;
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 pub enum PilotStatus {
     #[default]
     Normal,
@@ -21,7 +23,7 @@ impl fmt::Display for PilotStatus {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub enum Rank {
     #[default]
     Rookie,
@@ -40,8 +42,9 @@ impl fmt::Display for Rank {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Pilot {
+    pub id: u64,
     pub name: String,
     pub kills: u64,
     pub rank: Rank,
@@ -52,7 +55,8 @@ pub struct Pilot {
 impl Default for Pilot {
     fn default() -> Self {
         Pilot {
-            name: "Pilot".to_string(), // TODO: replace with name generation function
+            id: 0,                     // assigned by the roster
+            name: "Pilot".to_string(), // real names come from resources::names::NameGenerator
             kills: 0,
             rank: Rank::default(),
             status: PilotStatus::default(),
@@ -63,12 +67,7 @@ impl Default for Pilot {
 
 impl Pilot {
     pub fn mark_kill(&mut self, enemy: &Threats) {
-        match enemy {
-            Threats::Mk1 => self.kills += 1,
-            Threats::Mk2 => self.kills += 2,
-            Threats::Mk3 => self.kills += 3,
-            Threats::None => {}
-        }
+        self.kills += enemy.kill_value();
     }
     pub fn rank_up(&mut self) {
         if self.kills >= 6 {
@@ -0,0 +1,170 @@
+use serde::Serialize;
+
+use crate::app::App;
+
+use super::{
+    history::Snapshot,
+    parts::{Part, Parts},
+    scout::{scout_repair, scout_repair_cost},
+    ship::{
+        subsystem_repair, subsystem_repair_cost, subsystem_upgrade, tier_cost, upgrade_tier,
+        Status, SUBSYSTEM_NAMES,
+    },
+};
+
+/// one repair or upgrade the planner can queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Job {
+    Hull,             // one point of hull damage
+    Subsystem(usize), // Status tab index, 1-5
+    Scout(u64),       // scout id
+    Upgrade(usize),   // next tier for the subsystem at this Status tab index
+}
+
+/// a job on the planner's list, what it costs and whether it's queued
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanEntry {
+    pub job: Job,
+    pub label: String,
+    pub part: Part,
+    pub cost: u64,
+    pub queued: bool,
+}
+
+/// every repair and upgrade on offer this upkeep, worked out before anything is spent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairPlan {
+    pub entries: Vec<PlanEntry>,
+    pub selected: usize,
+}
+
+impl RepairPlan {
+    /// lists every damaged hull point, subsystem and scout, then every upgrade still to buy
+    /// costs come from `subsystem_repair_cost`, `scout_repair_cost` and `tier_cost`, the same
+    /// ones the repairs and upgrades themselves charge
+    pub fn new(app: &App) -> Self {
+        let mut entries = Vec::new();
+        let mut add = |job, label: String, part, cost| {
+            entries.push(PlanEntry {
+                job,
+                label,
+                part,
+                cost,
+                queued: false,
+            });
+        };
+        for point in 1..=app.hull_damage {
+            add(
+                Job::Hull,
+                format!("Hull damage {point}/{}", app.hull_damage),
+                Part::Plating,
+                subsystem_repair_cost(0),
+            );
+        }
+        let systems = [
+            &app.engine,
+            &app.mining_laser,
+            &app.scout_bay,
+            &app.sick_bay,
+            &app.sensors,
+        ];
+        for (i, system) in systems.iter().enumerate() {
+            if system.status != Status::Normal {
+                let subsystem = i + 1;
+                add(
+                    Job::Subsystem(subsystem),
+                    format!("{} ({})", SUBSYSTEM_NAMES[subsystem], system.status),
+                    Part::for_subsystem(subsystem),
+                    subsystem_repair_cost(subsystem),
+                );
+            }
+        }
+        for scout in &app.roster.scouts {
+            let Some(cost) = scout_repair_cost(&scout.ship.damage) else {
+                continue;
+            };
+            add(
+                Job::Scout(scout.id),
+                format!("{} ({})", scout.ship.name, scout.ship.damage),
+                Part::Avionics,
+                cost,
+            );
+        }
+        for (subsystem, name) in SUBSYSTEM_NAMES.iter().enumerate() {
+            let tier = upgrade_tier(app, subsystem);
+            if let Some(cost) = tier_cost(tier) {
+                add(
+                    Job::Upgrade(subsystem),
+                    format!("Upgrade {name} to tier {}", tier + 1),
+                    Part::for_subsystem(subsystem),
+                    cost,
+                );
+            }
+        }
+        RepairPlan {
+            entries,
+            selected: 0,
+        }
+    }
+
+    /// queues or unqueues the selected entry
+    pub fn toggle(&mut self) {
+        if let Some(entry) = self.entries.get_mut(self.selected) {
+            entry.queued = !entry.queued;
+        }
+    }
+
+    /// parts left once the queue is paid for in list order, or the first job that can't be paid
+    pub fn budget(&self, parts: &Parts) -> Result<Parts, String> {
+        let mut left = parts.clone();
+        for entry in self.entries.iter().filter(|x| x.queued && x.cost > 0) {
+            left.spend(entry.part, entry.cost)
+                .map_err(|_| format!("Not enough parts left for {}.", entry.label))?;
+        }
+        Ok(left)
+    }
+
+    /// queues every repair the parts will stretch to, hull first, then subsystems, then scouts
+    /// upgrades are left for the player to pick
+    pub fn suggest(&mut self, parts: &Parts) {
+        for i in 0..self.entries.len() {
+            let wanted = !matches!(self.entries[i].job, Job::Upgrade(_));
+            self.entries[i].queued = wanted;
+            if wanted && self.budget(parts).is_err() {
+                self.entries[i].queued = false;
+            }
+        }
+    }
+}
+
+/// applies every queued job, or nothing at all if the parts won't cover the whole queue
+pub fn apply_plan(app: &mut App, plan: &RepairPlan) -> Result<String, String> {
+    let left = plan.budget(&app.parts)?;
+    let queued: Vec<&PlanEntry> = plan.entries.iter().filter(|x| x.queued).collect();
+    if queued.is_empty() {
+        return Err("Nothing queued - <Space> adds the selected job to the plan.".to_string());
+    }
+    // the budget can't see every way a job fails (an upgrade already at the top tier, or the
+    // same repair queued twice), so anything done before a failing job is put back
+    let before = Snapshot::take(app);
+    for entry in &queued {
+        let done = match entry.job {
+            Job::Hull => subsystem_repair(app, 0),
+            Job::Subsystem(subsystem) => subsystem_repair(app, subsystem),
+            Job::Scout(id) => match app.roster.scouts.iter().position(|x| x.id == id) {
+                Some(index) => scout_repair(app, index),
+                None => Err(format!("{} isn't in the hangar any more.", entry.label)),
+            },
+            Job::Upgrade(subsystem) => subsystem_upgrade(app, subsystem),
+        };
+        if let Err(reason) = done {
+            before.restore(app);
+            return Err(format!("Plan not carried out: {reason}"));
+        }
+    }
+    Ok(format!(
+        "Plan carried out: {} job(s) done, {} parts left.",
+        queued.len(),
+        left.total()
+    ))
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{
+    pilot::{Pilot, PilotStatus},
+    ship::{FlightPosition, Scout, ShipDamage},
+};
+
+/// every pilot and scout in the campaign, each with a stable id
+/// the hangar/crew order is the order of the vectors, `assignment` says who flies what
+/// (scout id -> pilot id), so moving, injuring or promoting a pilot only ever touches one record
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Roster {
+    pub pilots: Vec<Pilot>,
+    pub scouts: Vec<Scout>,
+    pub assignment: BTreeMap<u64, u64>,
+    #[serde(skip)]
+    next_id: u64,
+}
+
+impl Roster {
+    /// hands out the next unused id, pilots and scouts share one counter
+    fn new_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// adds a pilot to the crew and returns its id
+    pub fn add_pilot(&mut self, mut pilot: Pilot) -> u64 {
+        pilot.id = self.new_id();
+        let id = pilot.id;
+        self.pilots.push(pilot);
+        id
+    }
+
+    /// adds a scout to the hangar and returns its id
+    pub fn add_scout(&mut self, mut scout: Scout) -> u64 {
+        scout.id = self.new_id();
+        let id = scout.id;
+        self.scouts.push(scout);
+        id
+    }
+
+    /// puts a pilot in a scout, taking them out of any other scout first
+    pub fn assign(&mut self, scout_id: u64, pilot_id: u64) {
+        self.assignment.retain(|_, pilot| *pilot != pilot_id);
+        self.assignment.insert(scout_id, pilot_id);
+    }
+
+    pub fn pilot(&self, id: u64) -> Option<&Pilot> {
+        self.pilots.iter().find(|x| x.id == id)
+    }
+
+    pub fn pilot_mut(&mut self, id: u64) -> Option<&mut Pilot> {
+        self.pilots.iter_mut().find(|x| x.id == id)
+    }
+
+    pub fn scout(&self, id: u64) -> Option<&Scout> {
+        self.scouts.iter().find(|x| x.id == id)
+    }
+
+    /// pilot flying the given scout
+    pub fn pilot_of(&self, scout_id: u64) -> Option<&Pilot> {
+        self.assignment
+            .get(&scout_id)
+            .and_then(|pilot_id| self.pilot(*pilot_id))
+    }
+
+    /// scout the given pilot is assigned to
+    pub fn scout_of(&self, pilot_id: u64) -> Option<&Scout> {
+        self.assignment
+            .iter()
+            .find(|(_, pilot)| **pilot == pilot_id)
+            .and_then(|(scout_id, _)| self.scout(*scout_id))
+    }
+
+    /// scout and its pilot together, for rules that change both at once
+    pub fn crewed_mut(&mut self, scout_id: u64) -> Option<(&mut Scout, &mut Pilot)> {
+        let pilot_id = *self.assignment.get(&scout_id)?;
+        let scout = self.scouts.iter_mut().find(|x| x.id == scout_id)?;
+        let pilot = self.pilots.iter_mut().find(|x| x.id == pilot_id)?;
+        Some((scout, pilot))
+    }
+
+    /// name of the pilot with this id, for logs that outlive the pilot's place in the crew list
+    pub fn pilot_name(&self, id: u64) -> String {
+        self.pilot(id)
+            .map_or_else(|| format!("Pilot #{id}"), |x| x.name.clone())
+    }
+
+    /// swaps two pilots in the crew list along with the scouts they fly
+    pub fn swap_pilots(&mut self, a: usize, b: usize) {
+        if a == b || a >= self.pilots.len() || b >= self.pilots.len() {
+            return;
+        }
+        let (id_a, id_b) = (self.pilots[a].id, self.pilots[b].id);
+        for pilot in self.assignment.values_mut() {
+            if *pilot == id_a {
+                *pilot = id_b;
+            } else if *pilot == id_b {
+                *pilot = id_a;
+            }
+        }
+        self.pilots.swap(a, b);
+    }
+
+    /// moves the scout at this hangar index one position forward (toward the lead) or back
+    /// (toward the reserve), swapping with whoever holds that slot - the reserve holds any number
+    /// keeps the hangar sorted by position and returns the scout's new index
+    pub fn move_scout(&mut self, index: usize, forward: bool) -> Option<usize> {
+        let scout = self.scouts.get(index)?;
+        let (id, from) = (scout.id, scout.position);
+        let to = if forward { from.forward() } else { from.back() }?;
+        if to != FlightPosition::Reserve {
+            if let Some(other) = self.scouts.iter_mut().find(|x| x.position == to) {
+                other.position = from;
+            }
+        }
+        if let Some(scout) = self.scouts.iter_mut().find(|x| x.id == id) {
+            scout.position = to;
+        }
+        self.scouts.sort_by_key(|x| x.position);
+        self.scouts.iter().position(|x| x.id == id)
+    }
+
+    /// scout ids that launch when combat starts, lead first
+    /// reserve scouts stay docked, wrecked scouts and scouts without a living pilot can't fly
+    pub fn launch_formation(&self) -> Vec<u64> {
+        let mut formation: Vec<&Scout> = self
+            .scouts
+            .iter()
+            .filter(|x| x.position != FlightPosition::Reserve)
+            .filter(|x| matches!(x.ship.damage, ShipDamage::Normal | ShipDamage::Half))
+            .filter(|x| {
+                self.pilot_of(x.id)
+                    .is_some_and(|p| p.status != PilotStatus::Kia)
+            })
+            .collect();
+        formation.sort_by_key(|x| x.position);
+        formation.iter().map(|x| x.id).collect()
+    }
+}
@@ -0,0 +1,118 @@
+use core::fmt;
+
+use serde::Serialize;
+
+/// campaign settings layered on top of the printed rule tables
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rules {
+    pub boss_after_leaps: u64, // the capital ship turns up on this leap at the latest
+    pub boss_threshold: i64,   // ... or sooner on a modified threat roll this high
+    pub threat_modifier: i64,  // added to every threat roll
+    pub starting_fuel: u64,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            boss_after_leaps: 15,
+            boss_threshold: 20,
+            threat_modifier: 0,
+            starting_fuel: 6,
+        }
+    }
+}
+
+/// rule presets picked with --difficulty
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl Difficulty {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match text.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "Unknown difficulty '{text}' (easy, normal or hard)."
+            )),
+        }
+    }
+}
+
+impl Rules {
+    /// rules for a difficulty preset - easy starts with more fuel and calmer threat rolls,
+    /// hard with less fuel, rougher rolls and an earlier capital ship
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Rules {
+                boss_after_leaps: 18,
+                boss_threshold: 22,
+                threat_modifier: -1,
+                starting_fuel: 8,
+            },
+            Difficulty::Normal => Rules::default(),
+            Difficulty::Hard => Rules {
+                boss_after_leaps: 12,
+                boss_threshold: 18,
+                threat_modifier: 1,
+                starting_fuel: 5,
+            },
+        }
+    }
+
+    /// sets one rule by name
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let bad_value = || format!("Bad value '{value}' for {key}.");
+        match key {
+            "boss_after_leaps" => self.boss_after_leaps = value.parse().map_err(|_| bad_value())?,
+            "boss_threshold" => self.boss_threshold = value.parse().map_err(|_| bad_value())?,
+            "threat_modifier" => self.threat_modifier = value.parse().map_err(|_| bad_value())?,
+            "starting_fuel" => self.starting_fuel = value.parse().map_err(|_| bad_value())?,
+            _ => return Err(format!("Unknown rule '{key}'.")),
+        }
+        Ok(())
+    }
+
+    /// every rule as name and value, in file order
+    pub fn pairs(&self) -> [(&'static str, String); 4] {
+        [
+            ("boss_after_leaps", self.boss_after_leaps.to_string()),
+            ("boss_threshold", self.boss_threshold.to_string()),
+            ("threat_modifier", self.threat_modifier.to_string()),
+            ("starting_fuel", self.starting_fuel.to_string()),
+        ]
+    }
+
+    /// applies a rules file on top of these rules
+    /// one `name = value` per line, # starts a comment, rules that aren't mentioned are kept
+    pub fn apply_text(&mut self, text: &str) -> Result<(), String> {
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Line {}: expected name = value.", number + 1))?;
+            self.set(key.trim(), value.trim())
+                .map_err(|x| format!("Line {}: {x}", number + 1))?;
+        }
+        Ok(())
+    }
+}
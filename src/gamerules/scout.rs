@@ -1,22 +1,35 @@
 use crate::app::App;
 
-use super::ship::ShipDamage;
+use super::{parts::Part, ship::ShipDamage};
 
-pub fn scout_repair(app: &mut App, position: usize) {
-    match app.scouts[position].ship.damage {
-        ShipDamage::Normal => app.scouts[position].ship.damage = ShipDamage::Normal,
-        ShipDamage::Half => app.scouts[position].ship.damage = ShipDamage::Normal,
-        ShipDamage::Inoperable => {
-            if app.parts >= 1 {
-                app.parts -= 1;
-                app.scouts[position].ship.damage = ShipDamage::Normal;
-            }
-        }
-        ShipDamage::Destroyed => {
-            if app.parts >= 6 {
-                app.parts -= 6;
-                app.scouts[position].ship.damage = ShipDamage::Normal;
-            }
-        }
+/// avionics it takes to repair a scout with this damage, None if there's nothing to repair
+/// half damage is patched up for free, inoperable scouts take 1 and destroyed ones a rebuild
+pub fn scout_repair_cost(damage: &ShipDamage) -> Option<u64> {
+    match damage {
+        ShipDamage::Normal => None,
+        ShipDamage::Half => Some(0),
+        ShipDamage::Inoperable => Some(1),
+        ShipDamage::Destroyed => Some(6),
     }
 }
+
+/// repairs the scout at this hangar position, inoperable and destroyed scouts need avionics
+/// nothing is spent or changed if it isn't damaged or the parts won't cover it
+pub fn scout_repair(app: &mut App, position: usize) -> Result<String, String> {
+    let scout = app
+        .roster
+        .scouts
+        .get_mut(position)
+        .ok_or_else(|| "No scout in that hangar slot.".to_string())?;
+    let cost = scout_repair_cost(&scout.ship.damage)
+        .ok_or_else(|| format!("{} isn't damaged.", scout.ship.name))?;
+    let paid = match cost {
+        0 => "free".to_string(),
+        _ => match app.parts.spend(Part::Avionics, cost)? {
+            Some(converted) => converted,
+            None => format!("{cost} {}", Part::Avionics),
+        },
+    };
+    scout.ship.damage = ShipDamage::Normal;
+    Ok(format!("{} repaired ({paid}).", scout.ship.name))
+}
@@ -0,0 +1,147 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+
+use crate::resources::star_names::STAR_NAMES;
+
+use super::{outpost::Outpost, rng};
+
+/// where the colony ship is trying to get back to, in map coordinates
+pub const HOME: (f64, f64) = (95.0, 30.0);
+/// map size, the canvas bounds are 0..WIDTH by 0..HEIGHT
+pub const WIDTH: f64 = 100.0;
+pub const HEIGHT: f64 = 60.0;
+/// map distance covered by a single leap, used to turn distances into leaps
+const LEAP_LENGTH: f64 = 10.0;
+/// chance out of 100 that a system has a trading outpost
+const OUTPOST_CHANCE: u32 = 15;
+
+/// a system we could jump to, with the modifiers it will put on the threat and scan rolls
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Destination {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    #[serde(skip)] // hidden, the sensor readings say how much of these is known
+    pub threat_modifier: i64,
+    #[serde(skip)]
+    pub scan_modifier: i64,
+    pub outpost: Option<Outpost>,
+}
+
+impl Destination {
+    /// rough number of leaps from this system to home
+    pub fn leaps_home(&self) -> u64 {
+        let distance = ((HOME.0 - self.x).powi(2) + (HOME.1 - self.y).powi(2)).sqrt();
+        (distance / LEAP_LENGTH).ceil() as u64
+    }
+
+    /// what the sensors can make out about the system
+    /// detail follows `forecast_detail` - 1 threat, 2 fuel, 3 distance home, 4 exact modifiers
+    pub fn readings(&self, detail: u8) -> Vec<String> {
+        // outposts broadcast a beacon, no sensors needed to pick it up
+        let beacon = self.outpost.as_ref().map(|_| "Outpost beacon".to_string());
+        if detail == 0 {
+            let mut readings = vec!["No sensor reading".to_string()];
+            readings.extend(beacon);
+            return readings;
+        }
+        let threat = match self.threat_modifier {
+            x if x > 0 => "Hostile signatures",
+            0 => "Nothing unusual",
+            _ => "Looks quiet",
+        };
+        let mut readings = vec![format!("Threat: {threat}")];
+        if detail >= 2 {
+            let fuel = match self.scan_modifier {
+                x if x > 0 => "Promising",
+                0 => "Average",
+                _ => "Poor",
+            };
+            readings.push(format!("Fuel prospects: {fuel}"));
+        }
+        if detail >= 3 {
+            readings.push(format!("Home: ~{} leaps", self.leaps_home()));
+        }
+        if detail >= 4 {
+            readings.push(format!(
+                "Threat {:+} / Scan {:+}",
+                self.threat_modifier, self.scan_modifier
+            ));
+        }
+        readings.extend(beacon);
+        readings
+    }
+}
+
+/// the systems we've passed through and the choices for the next jump
+#[derive(Debug, Clone)]
+pub struct SectorMap {
+    pub visited: Vec<(f64, f64)>, // the last entry is where the colony ship is now
+    pub current: Option<Destination>,
+    pub choices: Vec<Destination>,
+    pub selected: usize,
+}
+
+impl Default for SectorMap {
+    fn default() -> Self {
+        let mut map = SectorMap {
+            visited: vec![(5.0, HEIGHT / 2.0)],
+            current: None,
+            choices: Vec::new(),
+            selected: 0,
+        };
+        map.generate_choices();
+        map
+    }
+}
+
+impl SectorMap {
+    /// where the colony ship is on the map
+    pub fn position(&self) -> (f64, f64) {
+        self.visited.last().copied().unwrap_or((5.0, HEIGHT / 2.0))
+    }
+
+    /// rolls two or three systems within a leap of here, generally heading toward home
+    pub fn generate_choices(&mut self) {
+        let mut rng = rng();
+        let (x, y) = self.position();
+        let count = rng.gen_range(2..=3);
+        self.choices = (0..count)
+            .map(|_| {
+                let name = format!(
+                    "{}-{}",
+                    STAR_NAMES.choose(&mut rng).unwrap_or(&"Unknown"),
+                    rng.gen_range(1..100)
+                );
+                let mut destination = Destination {
+                    name,
+                    x: (x + rng.gen_range(4.0..LEAP_LENGTH)).min(WIDTH - 2.0),
+                    y: (y + rng.gen_range(-LEAP_LENGTH..LEAP_LENGTH)).clamp(2.0, HEIGHT - 2.0),
+                    threat_modifier: rng.gen_range(-2..=2),
+                    scan_modifier: rng.gen_range(-1..=1),
+                    outpost: rng.gen_ratio(OUTPOST_CHANCE, 100).then(Outpost::generate),
+                };
+                // systems near home are better charted, easier to find fuel in
+                if destination.leaps_home() <= 2 {
+                    destination.scan_modifier += 1;
+                }
+                destination
+            })
+            .collect();
+        self.selected = 0;
+    }
+
+    /// destination picked for the next jump
+    pub fn selected(&self) -> Option<&Destination> {
+        self.choices.get(self.selected)
+    }
+
+    /// moves to the picked destination and lays out the choices for the jump after it
+    pub fn jump(&mut self) {
+        if let Some(destination) = self.selected().cloned() {
+            self.visited.push((destination.x, destination.y));
+            self.current = Some(destination);
+        }
+        self.generate_choices();
+    }
+}
@@ -1,11 +1,13 @@
 use core::fmt;
 
+use serde::Serialize;
+
 use crate::app::App;
 
-use super::pilot::Pilot;
+use super::parts::Part;
 
 /// SubSystem status (100/66/33/0%)
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
 pub enum Status {
     #[default]
     Normal,
@@ -27,7 +29,7 @@ impl fmt::Display for Status {
 }
 
 /// Scout damage
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub enum ShipDamage {
     #[default]
     Normal,
@@ -48,15 +50,79 @@ impl fmt::Display for ShipDamage {
     }
 }
 
-/// represents each subsystem, tracks status and upgrade
-#[derive(Debug, Default)]
+/// where a scout flies in the formation - five launch slots plus the reserve, which stays docked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum FlightPosition {
+    Lead,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    #[default]
+    Reserve,
+}
+
+impl fmt::Display for FlightPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            FlightPosition::Lead => "Lead",
+            FlightPosition::Second => "Second",
+            FlightPosition::Third => "Third",
+            FlightPosition::Fourth => "Fourth",
+            FlightPosition::Fifth => "Fifth",
+            FlightPosition::Reserve => "Reserve",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl FlightPosition {
+    /// every position in formation order
+    pub const ALL: [FlightPosition; 6] = [
+        FlightPosition::Lead,
+        FlightPosition::Second,
+        FlightPosition::Third,
+        FlightPosition::Fourth,
+        FlightPosition::Fifth,
+        FlightPosition::Reserve,
+    ];
+
+    /// position one step toward the lead, None if already leading
+    pub fn forward(&self) -> Option<FlightPosition> {
+        let i = FlightPosition::ALL.iter().position(|x| x == self)?;
+        i.checked_sub(1).map(|i| FlightPosition::ALL[i])
+    }
+
+    /// position one step toward the reserve, None if already in reserve
+    pub fn back(&self) -> Option<FlightPosition> {
+        let i = FlightPosition::ALL.iter().position(|x| x == self)?;
+        FlightPosition::ALL.get(i + 1).copied()
+    }
+}
+
+/// represents each subsystem, tracks status and upgrade tier (0 is stock)
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct SubSystem {
     pub status: Status,
-    pub upgrade: bool,
+    pub upgrade: u8,
 }
 
+/// highest upgrade tier any subsystem can reach
+pub const MAX_TIER: u8 = 3;
+/// laser kill points it takes to earn the mining laser each tier
+pub const LASER_KILLS_PER_TIER: u64 = 5;
+/// subsystem names in the Status tab's order, the hull first
+pub const SUBSYSTEM_NAMES: [&str; 6] = [
+    "Hull",
+    "Engines",
+    "Mining Laser",
+    "Scout Bay",
+    "Sick Bay",
+    "Sensors",
+];
+
 /// represents each scout ship, tracks damage and name
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Ship {
     pub name: String,
     pub damage: ShipDamage,
@@ -71,39 +137,146 @@ impl Default for Ship {
     }
 }
 
-/// Scout struct with Pilot, Ship, and Position
-#[derive(Debug, Clone, Default)]
+/// Scout struct with roster id, Ship, and Position - the pilot flying it is tracked by the roster
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct Scout {
-    pub position: u64,
+    pub id: u64,
+    pub position: FlightPosition,
     pub ship: Ship,
-    pub pilot: Pilot,
-}
-
-/// repairs selected subsystem by one level
-pub fn subsystem_repair(app: &mut App, subsystem: usize) {
-    if app.parts >= 2 && subsystem > 0 {
-        let damage = if subsystem == 1 {
-            &mut app.engine.status
-        } else if subsystem == 2 {
-            &mut app.mining_laser.status
-        } else if subsystem == 3 {
-            &mut app.scout_bay.status
-        } else if subsystem == 4 {
-            &mut app.sick_bay.status
-        } else {
-            &mut app.sensors.status
-        };
-        if *damage != Status::Normal {
-            app.parts -= 2;
-            *damage = Status::Normal;
-            app.game_text = "Subsystem fully repaired with 2 parts.".to_string();
-            if subsystem == 0 {}
-        }
-    } else if app.parts >= 1 && subsystem == 0 && app.hull_damage > 0 {
-        app.parts -= 1;
+}
+
+/// parts a repair at this Status tab index takes, in the subsystem's own kind of part
+/// one per point of hull damage, any other subsystem is fixed outright for 2
+pub fn subsystem_repair_cost(subsystem: usize) -> u64 {
+    if subsystem == 0 {
+        1
+    } else {
+        2
+    }
+}
+
+/// repairs selected subsystem by one level, paid for in the subsystem's own kind of part
+/// nothing is spent or changed if it isn't damaged or the parts won't cover it
+pub fn subsystem_repair(app: &mut App, subsystem: usize) -> Result<String, String> {
+    let part = Part::for_subsystem(subsystem);
+    let cost = subsystem_repair_cost(subsystem);
+    let name = SUBSYSTEM_NAMES[subsystem.min(5)];
+    let damaged = match subsystem {
+        0 => app.hull_damage > 0,
+        _ => *subsystem_status_mut(app, subsystem) != Status::Normal,
+    };
+    if !damaged {
+        return Err(format!("{name} isn't damaged."));
+    }
+    let converted = app.parts.spend(part, cost)?;
+    let paid = match converted {
+        Some(converted) => converted,
+        None => format!("{cost} {part}"),
+    };
+    if subsystem == 0 {
         app.hull_damage -= 1;
-        app.game_text = "Hull damage repaired with 1 part.".to_string();
+        Ok(format!("Hull damage repaired ({paid})."))
     } else {
-        app.game_text = "Not enough parts to make this repair!".to_string();
+        *subsystem_status_mut(app, subsystem) = Status::Normal;
+        Ok(format!("{name} fully repaired ({paid})."))
+    }
+}
+
+/// status of the subsystem at this Status tab index, 1-5
+fn subsystem_status_mut(app: &mut App, subsystem: usize) -> &mut Status {
+    match subsystem {
+        1 => &mut app.engine.status,
+        2 => &mut app.mining_laser.status,
+        3 => &mut app.scout_bay.status,
+        4 => &mut app.sick_bay.status,
+        _ => &mut app.sensors.status,
+    }
+}
+
+/// damage the hull can take at this upgrade tier
+pub fn max_hull(tier: u8) -> u64 {
+    6 + tier as u64
+}
+
+/// parts of the subsystem's kind the next tier takes - 4, 6, then 8 - None once maxed out
+pub fn tier_cost(tier: u8) -> Option<u64> {
+    (tier < MAX_TIER).then(|| 4 + 2 * tier as u64)
+}
+
+/// what the subsystem at this Status tab index gets from an upgrade tier
+pub fn tier_effect(subsystem: usize, tier: u8) -> String {
+    if tier == 0 {
+        return "stock".to_string();
+    }
+    match subsystem {
+        0 => format!("hull holds {} damage", max_hull(tier)),
+        1 => format!("emergency jumps burn {tier} less fuel"),
+        2 => format!("+{tier} to mining laser rolls"),
+        3 => format!("recalls take {tier} fewer avionics"),
+        4 => format!("{tier} injured pilot(s) recover each upkeep"),
+        _ => format!("+{tier} sensor detail"),
+    }
+}
+
+/// upgrade tier of the subsystem at this Status tab index
+pub fn upgrade_tier(app: &App, subsystem: usize) -> u8 {
+    match subsystem {
+        0 => app.hull_upgrade,
+        1 => app.engine.upgrade,
+        2 => app.mining_laser.upgrade,
+        3 => app.scout_bay.upgrade,
+        4 => app.sick_bay.upgrade,
+        _ => app.sensors.upgrade,
+    }
+}
+
+/// mutable upgrade tier of the subsystem at this Status tab index
+fn upgrade_tier_mut(app: &mut App, subsystem: usize) -> &mut u8 {
+    match subsystem {
+        0 => &mut app.hull_upgrade,
+        1 => &mut app.engine.upgrade,
+        2 => &mut app.mining_laser.upgrade,
+        3 => &mut app.scout_bay.upgrade,
+        4 => &mut app.sick_bay.upgrade,
+        _ => &mut app.sensors.upgrade,
+    }
+}
+
+/// buys the next upgrade tier for the subsystem at this Status tab index
+pub fn subsystem_upgrade(app: &mut App, subsystem: usize) -> Result<String, String> {
+    let tier = upgrade_tier(app, subsystem);
+    let name = SUBSYSTEM_NAMES[subsystem.min(5)];
+    let cost = tier_cost(tier).ok_or_else(|| format!("{name} is fully upgraded."))?;
+    let part = Part::for_subsystem(subsystem);
+    let converted = app.parts.spend(part, cost)?;
+    *upgrade_tier_mut(app, subsystem) = tier + 1;
+    let paid = match converted {
+        Some(converted) => converted,
+        None => format!("{cost} {part}"),
+    };
+    Ok(format!(
+        "{name} upgraded to tier {} ({paid}): {}.",
+        tier + 1,
+        tier_effect(subsystem, tier + 1)
+    ))
+}
+
+/// mining laser tier earned from its kill record - never takes a tier away
+pub fn laser_tier_progress(app: &mut App) {
+    let earned = (app.laser_kills / LASER_KILLS_PER_TIER).min(MAX_TIER as u64) as u8;
+    if earned > app.mining_laser.upgrade {
+        app.mining_laser.upgrade = earned;
+    }
+}
+
+/// free upgrade tier to the least upgraded system, the first in the Status tab's order on a tie
+/// returns the name of what was upgraded, None if everything is already maxed out
+pub fn free_upgrade(app: &mut App) -> Option<&'static str> {
+    let subsystem = (0..SUBSYSTEM_NAMES.len()).min_by_key(|x| upgrade_tier(app, *x))?;
+    let tier = upgrade_tier_mut(app, subsystem);
+    if *tier >= MAX_TIER {
+        return None;
     }
+    *tier += 1;
+    Some(SUBSYSTEM_NAMES[subsystem])
 }
@@ -1,11 +1,37 @@
 use core::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use serde::{Serialize, Serializer};
+
+/// targetable sections of the capital ship, each fought as its own entry in the enemy table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Component {
+    Shields,
+    Guns,
+    Engines,
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Component::Shields => "Shields",
+            Component::Guns => "Guns",
+            Component::Engines => "Engines",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Threats {
     None,
     Mk1,
     Mk2,
     Mk3,
+    Bomber,      // only goes after the colony ship hull
+    Carrier,     // launches a MK1 at the end of every round
+    Interceptor, // always goes after the lead scout
+    Jammer,      // mining laser can't fire while it's in the fight
+    Capital(Component),
 }
 
 impl fmt::Display for Threats {
@@ -15,21 +41,128 @@ impl fmt::Display for Threats {
             Threats::Mk1 => "MK1",
             Threats::Mk2 => "MK2",
             Threats::Mk3 => "MK3",
+            Threats::Bomber => "Bomber",
+            Threats::Carrier => "Carrier",
+            Threats::Interceptor => "Interceptor",
+            Threats::Jammer => "Jammer",
+            Threats::Capital(component) => return write!(f, "Capital {component}"),
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl Threats {
+    /// one of the archetypes with its own rules rather than a plain fighter
+    pub fn is_special(&self) -> bool {
+        matches!(
+            self,
+            Threats::Bomber
+                | Threats::Carrier
+                | Threats::Interceptor
+                | Threats::Jammer
+                | Threats::Capital(_)
+        )
+    }
+
+    /// one of the capital ship's sections
+    pub fn is_capital(&self) -> bool {
+        matches!(self, Threats::Capital(_))
+    }
+
+    /// kill points for destroying one, counted towards pilot ranks and mining laser tiers
+    pub fn kill_value(&self) -> u64 {
+        match self {
+            Threats::Mk1 | Threats::Jammer => 1,
+            Threats::Mk2 | Threats::Bomber | Threats::Interceptor => 2,
+            Threats::Mk3 | Threats::Capital(_) => 3,
+            Threats::Carrier => 4,
+            Threats::None => 0,
+        }
+    }
+}
+
+/// where an enemy fighter stands in the fight
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub enum FighterState {
+    #[default]
+    Active,
+    Destroyed,
+    Retreated, // out of fuel and broke off, no kill and no wreck
+}
+
+impl fmt::Display for FighterState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            FighterState::Active => "Active",
+            FighterState::Destroyed => "Destroyed",
+            FighterState::Retreated => "Retreated",
         };
         write!(f, "{printable}")
     }
 }
 
 /// enemy fighter stats
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Fighter {
     pub model: Threats,
     pub hp: u64,
     pub guns: u64,
-    pub fuel: u64,
+    #[serde(serialize_with = "serialize_fuel")]
+    pub fuel: u64, // u64::MAX for capital ship sections, which never run out
+    pub state: FighterState,
+}
+
+/// unlimited fuel goes out as null rather than the u64::MAX it's kept as
+fn serialize_fuel<S: Serializer>(fuel: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    match *fuel {
+        u64::MAX => serializer.serialize_none(),
+        fuel => serializer.serialize_some(&fuel),
+    }
 }
 
 impl Fighter {
+    /// still in the fight - can shoot and be shot at
+    pub fn is_active(&self) -> bool {
+        self.state == FighterState::Active
+    }
+
+    /// applies damage, returns true if this is the hit that destroyed the fighter
+    pub fn take_damage(&mut self, damage: u64) -> bool {
+        self.hp = self.hp.saturating_sub(damage);
+        if self.hp == 0 && self.is_active() {
+            self.state = FighterState::Destroyed;
+            return true;
+        }
+        false
+    }
+
+    /// fuel left as shown in the enemy table, "-" for sections that never run out
+    pub fn fuel_text(&self) -> String {
+        if self.fuel == u64::MAX {
+            "-".to_string()
+        } else {
+            self.fuel.to_string()
+        }
+    }
+
+    /// end of round fuel burn, returns true if the fighter just ran dry and broke off
+    pub fn burn_fuel(&mut self) -> bool {
+        if !self.is_active() || self.model.is_capital() {
+            return false;
+        }
+        self.fuel = self.fuel.saturating_sub(1);
+        if self.fuel == 0 {
+            self.state = FighterState::Retreated;
+            return true;
+        }
+        false
+    }
+
+    /// last round this fighter will fight before breaking off, given the current round
+    pub fn breaks_off_after(&self, round: u64) -> u64 {
+        round.saturating_add(self.fuel.saturating_sub(1))
+    }
+
     /// returns Mk1 Fighter
     pub fn mk1() -> Fighter {
         Fighter {
@@ -37,6 +170,7 @@ impl Fighter {
             hp: 2,
             guns: 1,
             fuel: 3,
+            state: FighterState::Active,
         }
     }
     /// returns Mk2 Fighter
@@ -46,6 +180,7 @@ impl Fighter {
             hp: 5,
             guns: 2,
             fuel: 4,
+            state: FighterState::Active,
         }
     }
     /// returns Mk3 Fighter
@@ -55,8 +190,110 @@ impl Fighter {
             hp: 8,
             guns: 4,
             fuel: 5,
+            state: FighterState::Active,
+        }
+    }
+    /// returns a capital ship section - shields soak up fire, guns do the shooting and the
+    /// engines hold out the longest, none of them ever run out of fuel
+    pub fn capital(component: Component) -> Fighter {
+        let (hp, guns) = match component {
+            Component::Shields => (6, 0),
+            Component::Guns => (8, 3),
+            Component::Engines => (6, 0),
+        };
+        Fighter {
+            model: Threats::Capital(component),
+            hp,
+            guns,
+            fuel: u64::MAX,
+            state: FighterState::Active,
+        }
+    }
+    /// returns Bomber
+    pub fn bomber() -> Fighter {
+        Fighter {
+            model: Threats::Bomber,
+            hp: 4,
+            guns: 2,
+            fuel: 4,
+            state: FighterState::Active,
+        }
+    }
+    /// returns Carrier
+    pub fn carrier() -> Fighter {
+        Fighter {
+            model: Threats::Carrier,
+            hp: 10,
+            guns: 1,
+            fuel: 6,
+            state: FighterState::Active,
+        }
+    }
+    /// returns Interceptor
+    pub fn interceptor() -> Fighter {
+        Fighter {
+            model: Threats::Interceptor,
+            hp: 3,
+            guns: 2,
+            fuel: 3,
+            state: FighterState::Active,
         }
     }
+    /// returns Jammer
+    pub fn jammer() -> Fighter {
+        Fighter {
+            model: Threats::Jammer,
+            hp: 3,
+            guns: 1,
+            fuel: 4,
+            state: FighterState::Active,
+        }
+    }
+}
+
+/// what's left of an enemy fighter once combat is over
+/// fighters that ran out of fuel broke off and left nothing behind to salvage
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Wreck {
+    pub model: Threats,
+    pub destroyed: bool,
+}
+
+impl fmt::Display for Wreck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.destroyed {
+            write!(f, "{} hulk", self.model)
+        } else {
+            write!(f, "{} (fled)", self.model)
+        }
+    }
+}
+
+/// salvage record of every fighter in the fight, destroyed or fled
+pub fn wreckage(fighters: &[Fighter]) -> Vec<Wreck> {
+    fighters
+        .iter()
+        .map(|x| Wreck {
+            model: x.model.clone(),
+            destroyed: x.state == FighterState::Destroyed,
+        })
+        .collect()
+}
+
+/// the capital ship and its escort
+pub fn boss_group() -> Vec<Threats> {
+    vec![
+        Threats::Capital(Component::Shields),
+        Threats::Capital(Component::Guns),
+        Threats::Capital(Component::Engines),
+        Threats::Mk2,
+        Threats::Mk2,
+    ]
+}
+
+/// fighter of the given type at full fuel and hp, None for Threats::None
+pub fn fresh_fighter(model: &Threats) -> Option<Fighter> {
+    threats_to_fighters(std::slice::from_ref(model)).pop()
 }
 
 /// turn vector of Threats enum to vector of Fighter Structs
@@ -69,6 +306,11 @@ pub fn threats_to_fighters(v: &[Threats]) -> Vec<Fighter> {
             Threats::Mk1 => fighters.push(Fighter::mk1()),
             Threats::Mk2 => fighters.push(Fighter::mk2()),
             Threats::Mk3 => fighters.push(Fighter::mk3()),
+            Threats::Bomber => fighters.push(Fighter::bomber()),
+            Threats::Carrier => fighters.push(Fighter::carrier()),
+            Threats::Interceptor => fighters.push(Fighter::interceptor()),
+            Threats::Jammer => fighters.push(Fighter::jammer()),
+            Threats::Capital(component) => fighters.push(Fighter::capital(*component)),
         }
     }
     fighters
@@ -0,0 +1,435 @@
+use core::fmt;
+
+use serde::Serialize;
+
+use crate::app::App;
+
+use super::{
+    combat::{gather_pursuers, Combat},
+    events::{event_check, PendingEvent},
+    game_functions::{
+        assess_threat, emergency_jump_cost, leap_into_system, search_wreckage, system_scan,
+    },
+    parts::sort_salvage,
+    pilot::PilotStatus,
+    ship::{free_upgrade, ShipDamage, Status},
+    threat::{threats_to_fighters, Threats},
+    Leap,
+};
+
+/// phases of a leap, in order
+/// the current phase is the one waiting to be resolved, advancing resolves it and moves on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum Phase {
+    #[default]
+    Leap,
+    AssessThreat,
+    Combat,
+    Salvage,
+    Scan,
+    Upkeep,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Phase::Leap => "Leap",
+            Phase::AssessThreat => "Assess Threat",
+            Phase::Combat => "Combat",
+            Phase::Salvage => "Salvage",
+            Phase::Scan => "Scan",
+            Phase::Upkeep => "Upkeep",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+/// everything the player can try to do that the phase rules care about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Action {
+    Advance,
+    Attack,
+    Laser,
+    EnemyTurn,
+    AutoResolve,
+    Recall,
+    EmergencyJump,
+    Repair,
+    Upgrade,
+    Reposition,
+    Trade,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let printable = match *self {
+            Action::Advance => "advance",
+            Action::Attack => "attack",
+            Action::Laser => "fire the mining laser",
+            Action::EnemyTurn => "run the enemy turn",
+            Action::AutoResolve => "auto-resolve",
+            Action::Recall => "recall a scout",
+            Action::EmergencyJump => "make an emergency jump",
+            Action::Repair => "repair",
+            Action::Upgrade => "upgrade",
+            Action::Reposition => "change the formation",
+            Action::Trade => "trade",
+        };
+        write!(f, "{printable}")
+    }
+}
+
+impl Phase {
+    /// every phase in turn order
+    pub const ALL: [Phase; 6] = [
+        Phase::Leap,
+        Phase::AssessThreat,
+        Phase::Combat,
+        Phase::Salvage,
+        Phase::Scan,
+        Phase::Upkeep,
+    ];
+
+    /// phase that follows this one, upkeep wraps around to the next leap
+    pub fn next(&self) -> Phase {
+        match self {
+            Phase::Leap => Phase::AssessThreat,
+            Phase::AssessThreat => Phase::Combat,
+            Phase::Combat => Phase::Salvage,
+            Phase::Salvage => Phase::Scan,
+            Phase::Scan => Phase::Upkeep,
+            Phase::Upkeep => Phase::Leap,
+        }
+    }
+
+    /// position in the turn, for the progress bar
+    pub fn index(&self) -> usize {
+        Phase::ALL.iter().position(|x| x == self).unwrap_or(0)
+    }
+
+    /// actions the player may take while this phase is waiting to be resolved
+    pub fn allowed_actions(&self) -> &'static [Action] {
+        match self {
+            Phase::Combat => &[
+                Action::Advance,
+                Action::Attack,
+                Action::Laser,
+                Action::EnemyTurn,
+                Action::AutoResolve,
+                Action::Recall,
+                Action::EmergencyJump,
+            ],
+            Phase::Upkeep => &[
+                Action::Advance,
+                Action::Repair,
+                Action::Upgrade,
+                Action::Reposition,
+                Action::Trade,
+            ],
+            _ => &[Action::Advance, Action::Reposition],
+        }
+    }
+
+    pub fn allows(&self, action: Action) -> bool {
+        self.allowed_actions().contains(&action)
+    }
+
+    /// what has to be true before the phase can be resolved and left
+    pub fn exit_condition(&self) -> &'static str {
+        match self {
+            Phase::Leap => "Leap into the next system.",
+            Phase::AssessThreat => "Roll for threats.",
+            Phase::Combat => "Every enemy destroyed or out of fuel.",
+            Phase::Salvage => "Search the wreckage.",
+            Phase::Scan => "Scan the system.",
+            Phase::Upkeep => "Finish repairs and upgrades.",
+        }
+    }
+
+    /// checks the exit condition against the game state
+    pub fn ready_to_exit(&self, app: &App) -> Result<(), String> {
+        match self {
+            Phase::AssessThreat if app.event.is_some() => {
+                Err("Deal with the event first.".to_string())
+            }
+            Phase::Combat if app.in_combat => {
+                Err("Combat isn't over yet - destroy or outlast every enemy first.".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// checks an action against the current phase, the error says why it isn't allowed
+pub fn check_action(app: &App, action: Action) -> Result<(), String> {
+    let phase = app.phase;
+    if !phase.allows(action) {
+        let allowed = phase
+            .allowed_actions()
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        return Err(format!(
+            "Can't {action} during the {phase} phase (allowed: {allowed})."
+        ));
+    }
+    if action == Action::Advance {
+        phase.ready_to_exit(app)?;
+    }
+    Ok(())
+}
+
+/// resolves the current phase and moves to the next one
+pub fn advance(app: &mut App) -> Result<(), String> {
+    check_action(app, Action::Advance)?;
+    match app.phase {
+        Phase::Leap => {
+            app.current_leap = Leap::default(); // reset current leap log
+            app.current_leap.number = app.log.len() as u64 + 1;
+            app.sector.jump();
+            let system = app
+                .sector
+                .current
+                .as_ref()
+                .map_or_else(|| "a new system".to_string(), |x| x.name.clone());
+            app.game_text = format!("Jumping into {system} ...");
+            leap_into_system(app);
+            let (card, record) = event_check();
+            app.current_leap.rolls.push(record);
+            if let Some(card) = card {
+                app.event = Some(PendingEvent { card, selected: 0 });
+                app.game_text += &format!("  {}!", card.name);
+            }
+        }
+        Phase::AssessThreat => {
+            app.game_text = "Assessing threats ...".to_string();
+            let scout_vec = app.roster.launch_formation();
+            let (threats, record) = assess_threat(app);
+            app.current_leap.rolls.push(record);
+            let mut enemy_vec = threats.unwrap_or_default();
+            let mut enemy_stats = threats_to_fighters(&enemy_vec);
+            // whoever followed us out of the last fight joins in with the hp they had left
+            let pursuers = std::mem::take(&mut app.pursuers);
+            if !pursuers.is_empty() {
+                app.game_text += &format!("{} pursuer(s) catch up!  ", pursuers.len());
+            }
+            for fighter in pursuers {
+                enemy_vec.push(fighter.model.clone());
+                enemy_stats.push(fighter);
+            }
+            if enemy_vec.is_empty() {
+                app.game_text += "Sector clear.  Whew!";
+                app.in_combat = false;
+                enemy_vec = vec![Threats::None];
+            } else {
+                app.game_text += "Enemy ships are preparing to engage!";
+                app.in_combat = true;
+            }
+            // update log with threat info
+            app.current_leap.threats = enemy_vec.clone();
+            app.current_leap.damage = vec![0; enemy_vec.len()];
+            app.wreckage = Vec::new();
+            app.combat = Some(Combat {
+                rounds: 1,
+                scout_turns: vec![false; scout_vec.len()],
+                scout_formation: scout_vec,
+                recalled: Vec::new(),
+                enemy_turns: vec![false; enemy_vec.len()],
+                enemy_stats,
+                enemy_formation: enemy_vec,
+                scout_half: true,
+                laser_fired: false,
+                combat_text: "Enemy ships sighted!  Prepare to engage!".to_string(),
+                log: Vec::new(),
+            });
+        }
+        Phase::Combat => {
+            app.game_text = "The system is quiet.  Time to look for salvage.".to_string();
+            // every enemy destroyed or broken off - the next leap is the first since an incident
+            if app.current_leap.threats.iter().any(|x| *x != Threats::None) {
+                app.incident = true;
+            }
+        }
+        Phase::Salvage => {
+            let hulks = app
+                .wreckage
+                .iter()
+                .filter(|x| x.destroyed)
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>();
+            let fled = app.wreckage.iter().filter(|x| !x.destroyed).count();
+            if !hulks.is_empty() {
+                let (count, record) = search_wreckage(&app.wreckage);
+                app.current_leap.rolls.push(record);
+                let parts = sort_salvage(&app.wreckage, count);
+                app.parts.merge(&parts);
+                app.current_leap.parts_found = count; // update log
+                app.game_text = format!(
+                    "You search through the wreckage ({}) and recover {parts}.",
+                    hulks.join(", ")
+                );
+            } else {
+                app.game_text = "No wreckage to salvage.".to_string();
+            }
+            if fled > 0 {
+                app.game_text += &format!("  {fled} fighter(s) fled and left nothing behind.");
+            }
+            // taking the capital ship apart is worth a full upgrade on top of the parts
+            let sections = app
+                .wreckage
+                .iter()
+                .filter(|x| x.destroyed && x.model.is_capital())
+                .count();
+            if sections == 3 && !app.boss_defeated {
+                app.boss_defeated = true;
+                let reward = match free_upgrade(app) {
+                    Some(system) => format!("  The capital ship's core yields a {system} upgrade!"),
+                    None => "  The capital ship's core holds nothing you haven't already got."
+                        .to_string(),
+                };
+                app.game_text += &reward;
+            }
+        }
+        Phase::Scan => {
+            let (fuel, scan_result, record) = system_scan(app);
+            app.current_leap.rolls.push(record);
+            app.fuel += fuel;
+            app.current_leap.fuel_found = fuel; // update log
+            app.game_text = format!(
+                "Scanning system... {scan_result} - gathered {fuel} fuel.  Make repairs and upkeep."
+            );
+            // TODO: handle anomoly and home scans
+        }
+        Phase::Upkeep => {
+            // scouts at 50% are repaired for free
+            // inoperable scouts can be repaired for 1 part
+            // each point of hull damage can be repaired for 1 part
+            // a scout can be scrapped for +4 parts
+            // repairing any system requires 2 parts
+            // upgrading a system costs 4 parts
+            // building a new scout costs 6 parts
+            // after every 5th leap you get a free upgrade
+            // injured pilots heal according to sick bay - do this last
+            // inoperable sick bay means newly injured pilots die
+            // start training up new pilots
+            app.game_text = "Upkeep done.  Ready to leap.".to_string();
+            let healed = sick_bay_recovery(app);
+            if !healed.is_empty() {
+                app.game_text += &format!("  Back on duty: {}.", healed.join(", "));
+            }
+            app.log.push(app.current_leap.clone());
+        }
+    }
+    app.phase = app.phase.next();
+    Ok(())
+}
+
+/// an upgraded sick bay puts one injured pilot per tier back on duty, if it works at all
+/// returns the names of the pilots who recovered
+pub fn sick_bay_recovery(app: &mut App) -> Vec<String> {
+    if app.sick_bay.status == Status::Inoperable {
+        return Vec::new();
+    }
+    app.roster
+        .pilots
+        .iter_mut()
+        .filter(|x| x.status == PilotStatus::Injured)
+        .take(app.sick_bay.upgrade as usize)
+        .map(|pilot| {
+            pilot.status = PilotStatus::Normal;
+            pilot.injury_timer = 0;
+            pilot.name.clone()
+        })
+        .collect()
+}
+
+/// checks an emergency jump can be made right now and returns the extra fuel it will burn
+pub fn check_emergency_jump(app: &App) -> Result<u64, String> {
+    check_action(app, Action::EmergencyJump)?;
+    let rounds = app.combat.as_ref().map_or(0, |x| x.rounds);
+    if !app.in_combat || rounds < 2 {
+        return Err("Emergency jump available starting in round 2 of combat.".to_string());
+    }
+    let extra = emergency_jump_cost(&app.engine)?;
+    if app.fuel < extra + 1 {
+        return Err(format!(
+            "Emergency jump needs {} fuel, you only have {}.",
+            extra + 1,
+            app.fuel
+        ));
+    }
+    Ok(extra)
+}
+
+/// scouts still out in the formation - an emergency jump would leave these behind
+pub fn stranded_scouts(app: &App) -> Vec<u64> {
+    let Some(combat) = app.combat.as_ref() else {
+        return Vec::new();
+    };
+    combat
+        .scout_formation
+        .iter()
+        .copied()
+        .filter(|id| !combat.recalled.contains(id))
+        .filter(|id| {
+            app.roster
+                .scout(*id)
+                .is_some_and(|x| x.ship.damage != ShipDamage::Destroyed)
+        })
+        .collect()
+}
+
+/// pilots of the stranded scouts - an emergency jump leaves them for dead
+pub fn stranded_pilots(app: &App) -> Vec<String> {
+    stranded_scouts(app)
+        .iter()
+        .filter_map(|id| app.roster.pilot_of(*id))
+        .map(|x| x.name.clone())
+        .collect()
+}
+
+/// jumps out of a fight straight into the next system
+/// scouts still out in the formation are left behind with their pilots, the wreckage is
+/// forfeit, and salvage, scan and upkeep are skipped - the jump itself counts as the next leap
+/// running away doesn't settle the incident, so leaps since incident keeps counting up through
+/// the jump like it would for any other leap
+pub fn emergency_jump(app: &mut App) -> Result<String, String> {
+    let extra = check_emergency_jump(app)?;
+    let mut abandoned = Vec::new();
+    let mut lost = Vec::new();
+    for scout_id in stranded_scouts(app) {
+        if let Some((scout, pilot)) = app.roster.crewed_mut(scout_id) {
+            scout.ship.damage = ShipDamage::Destroyed;
+            pilot.status = PilotStatus::Kia;
+            abandoned.push(scout.ship.name.clone());
+            lost.push(pilot.name.clone());
+        }
+    }
+    let fighters = app
+        .combat
+        .as_ref()
+        .map_or_else(Vec::new, |x| x.enemy_stats.clone());
+    let followed = gather_pursuers(app, &fighters, true);
+    app.fuel -= extra;
+    app.in_combat = false;
+    app.combat = None;
+    app.wreckage = Vec::new();
+    app.log.push(app.current_leap.clone());
+    app.phase = Phase::Leap;
+    advance(app)?;
+    let left = if abandoned.is_empty() {
+        "Every scout made it back aboard.".to_string()
+    } else {
+        format!(
+            "Left behind: {}.  Lost with them: {}.",
+            abandoned.join(", "),
+            lost.join(", ")
+        )
+    };
+    app.game_text = format!("Emergency jump!  Burned {extra} extra fuel.  {left}");
+    if followed > 0 {
+        app.game_text += &format!("  {followed} fighter(s) picked up our jump trail.");
+    }
+    Ok(app.game_text.clone())
+}
@@ -1,17 +1,65 @@
 // external dependencies
-use std::io;
+use std::{io, process};
 
 // local inclusions
 mod app;
+mod bot;
+mod cli;
 mod gamerules;
+mod replay;
 mod resources;
+mod sim;
 mod tui;
 use crate::tui::interface_core::{init, restore};
 use app::App;
+use cli::{Command, USAGE};
+use replay::Replay;
 
 fn main() -> io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::parse(&args).unwrap_or_else(|e| {
+        eprintln!("{e}\n\n{USAGE}");
+        process::exit(2);
+    });
+    let result = match command {
+        Command::Play(options) => match options.app() {
+            Ok(app) => return play(app),
+            Err(e) => Err(e),
+        },
+        Command::Bot(options) => match options.app() {
+            Ok(mut app) => return bot::run(&mut app),
+            Err(e) => Err(e),
+        },
+        Command::Replay(path) => {
+            let replay = Replay::load(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{path}: {e}")))?;
+            return play(App::replaying(replay));
+        }
+        Command::Sim(options) => cli::sim(&options),
+        Command::Export(path, format) => cli::export(&path, format),
+        Command::Validate(path) => cli::validate(&path),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// runs the TUI, saving the campaign as a replay on the way out
+fn play(mut app: App) -> io::Result<()> {
     let mut terminal = init()?;
-    let app_result = App::default().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     restore()?;
+    if app.playback.is_none() && !app.recording.commands.is_empty() {
+        match app.recording.save() {
+            Ok(path) => println!("Replay saved to {}", path.display()),
+            Err(e) => eprintln!("Couldn't save the replay: {e}"),
+        }
+    }
     app_result
 }
@@ -0,0 +1,222 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crossterm::event::KeyCode;
+
+use crate::{
+    gamerules::rules::Rules,
+    resources::names::{config_dir, list_lines},
+};
+
+/// first line of every replay file, bumped if the format ever changes
+const HEADER: &str = "lostship replay 1";
+/// fastest and slowest playback, in milliseconds between commands
+pub const MIN_DELAY: u64 = 25;
+pub const MAX_DELAY: u64 = 3200;
+
+/// a campaign's seed, rules, name lists and every key the player pressed, in order
+/// the same seed, rules, names and keys play out the same game, dice and all
+/// a replay is also the save file - loading one plays its keys back to rebuild the campaign
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules,
+    pub names: String, // the player's own name lists the campaign was dealt from, if any
+    pub commands: Vec<KeyCode>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, rules: Rules, names: String) -> Self {
+        Replay {
+            seed,
+            rules,
+            names,
+            commands: Vec::new(),
+        }
+    }
+
+    /// writes the replay in its text form - a header, the seed, the rules, the name lists, then
+    /// every command on one line
+    /// the name lists are a count of lines followed by the lines themselves, as in names.txt
+    /// keys are single characters, anything else is a name in angle brackets e.g. <Up>
+    pub fn to_text(&self) -> String {
+        let rules = self
+            .rules
+            .pairs()
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let names = list_lines(&self.names);
+        let mut text = format!("{HEADER}\nseed {}\nrules {rules}\n", self.seed);
+        if !names.is_empty() {
+            text += &format!("names {}\n", names.len());
+            for line in names {
+                text += &format!("{line}\n");
+            }
+        }
+        let commands: String = self.commands.iter().map(encode).collect();
+        text + &commands + "\n"
+    }
+
+    /// reads a replay back from its text form
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err("Not a Lost Ship replay file.".to_string());
+        }
+        let seed = lines
+            .next()
+            .and_then(|x| x.trim().strip_prefix("seed "))
+            .and_then(|x| x.parse().ok())
+            .ok_or_else(|| "Replay file is missing its seed.".to_string())?;
+        // replays without a rules line were played on the default rules
+        let mut lines = lines.peekable();
+        let mut rules = Rules::default();
+        if let Some(pairs) = lines.next_if(|x| x.starts_with("rules ")) {
+            for pair in pairs.split_whitespace().skip(1) {
+                let (key, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("Bad rule '{pair}' in replay."))?;
+                rules.set(key, value)?;
+            }
+        }
+        // nor were replays without names, on anything but the built in lists
+        let mut names = Vec::new();
+        if let Some(count) = lines.next_if(|x| x.starts_with("names ")) {
+            let count: usize = count[6..]
+                .trim()
+                .parse()
+                .map_err(|_| format!("Bad name list length '{}' in replay.", &count[6..]))?;
+            for _ in 0..count {
+                let line = lines
+                    .next()
+                    .ok_or_else(|| "Replay file ends in the middle of its names.".to_string())?;
+                names.push(line.trim());
+            }
+        }
+        let mut commands = Vec::new();
+        let mut chars = lines.flat_map(str::chars);
+        while let Some(c) = chars.next() {
+            if c != '<' {
+                commands.push(KeyCode::Char(c));
+                continue;
+            }
+            let name: String = chars.by_ref().take_while(|x| *x != '>').collect();
+            let code = decode(&name).ok_or_else(|| format!("Unknown key <{name}> in replay."))?;
+            commands.push(code);
+        }
+        Ok(Replay {
+            seed,
+            rules,
+            names: names.join("\n"),
+            commands,
+        })
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Replay::from_text(&text).map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+    }
+
+    /// saves to the replays folder in the config directory, named after the seed
+    /// another campaign on the same seed gets the next free number instead of overwriting it
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let dir = config_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?
+            .join("replays");
+        fs::create_dir_all(&dir)?;
+        for n in 1.. {
+            let path = match n {
+                1 => dir.join(format!("{}.replay", self.seed)),
+                n => dir.join(format!("{}-{n}.replay", self.seed)),
+            };
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(self.to_text().as_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("ran out of replay file numbers")
+    }
+}
+
+/// keys a replay can hold - the game ignores the rest, so they're never played at all
+pub fn recordable(code: &KeyCode) -> bool {
+    !encode(code).is_empty()
+}
+
+/// text form of a single key
+fn encode(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "<Space>".to_string(),
+        KeyCode::Char('<') => "<Lt>".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "<Up>".to_string(),
+        KeyCode::Down => "<Down>".to_string(),
+        KeyCode::Left => "<Left>".to_string(),
+        KeyCode::Right => "<Right>".to_string(),
+        KeyCode::Enter => "<Enter>".to_string(),
+        KeyCode::Esc => "<Esc>".to_string(),
+        KeyCode::Backspace => "<Bs>".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// key for a name in angle brackets
+fn decode(name: &str) -> Option<KeyCode> {
+    let code = match name {
+        "Space" => KeyCode::Char(' '),
+        "Lt" => KeyCode::Char('<'),
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Bs" => KeyCode::Backspace,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// a replay being played back in the TUI
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub replay: Replay,
+    pub position: usize, // commands already played
+    pub playing: bool,
+    pub delay: u64, // milliseconds between commands while playing
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Playback {
+            replay,
+            position: 0,
+            playing: false,
+            delay: 400,
+        }
+    }
+
+    /// next command to play, moving past it
+    pub fn next_command(&mut self) -> Option<KeyCode> {
+        let code = self.replay.commands.get(self.position).copied();
+        if code.is_some() {
+            self.position += 1;
+        } else {
+            self.playing = false;
+        }
+        code
+    }
+
+    pub fn finished(&self) -> bool {
+        self.position >= self.replay.commands.len()
+    }
+}
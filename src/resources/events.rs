@@ -0,0 +1,116 @@
+use crate::gamerules::events::{Effect, EventCard, EventChoice};
+
+/// every event that can be drawn between leaps, each equally likely
+pub const EVENT_DECK: &[EventCard] = &[
+    EventCard {
+        name: "Distress Call",
+        text: "A weak distress beacon pulses from a disabled freighter.  It could be survivors - or bait.",
+        choices: &[
+            EventChoice {
+                label: "Answer the call",
+                effects: &[Effect::Chance {
+                    target: 4,
+                    success: &[Effect::Parts(3), Effect::Fuel(1)],
+                    failure: &[Effect::HullDamage(1)],
+                }],
+            },
+            EventChoice {
+                label: "Ignore it",
+                effects: &[],
+            },
+        ],
+    },
+    EventCard {
+        name: "Derelict Colony Pods",
+        text: "Cryo pods drift around the wreck of another colony ship.  A few still show life signs.",
+        choices: &[
+            EventChoice {
+                label: "Take them aboard (1 fuel)",
+                effects: &[
+                    Effect::Fuel(-1),
+                    Effect::RecruitPilot,
+                    Effect::Chance {
+                        target: 4,
+                        success: &[Effect::RecruitPilot],
+                        failure: &[],
+                    },
+                ],
+            },
+            EventChoice {
+                label: "Strip the empty pods for parts",
+                effects: &[Effect::Parts(2)],
+            },
+            EventChoice {
+                label: "Leave them be",
+                effects: &[],
+            },
+        ],
+    },
+    EventCard {
+        name: "Micrometeorite Shower",
+        text: "Sensors light up with debris - a micrometeorite shower is sweeping across our path.",
+        choices: &[
+            EventChoice {
+                label: "Ride it out",
+                effects: &[
+                    Effect::HullDamage(1),
+                    Effect::Chance {
+                        target: 4,
+                        success: &[],
+                        failure: &[Effect::HullDamage(1)],
+                    },
+                ],
+            },
+            EventChoice {
+                label: "Burn hard to clear it (1 fuel)",
+                effects: &[
+                    Effect::Fuel(-1),
+                    Effect::Chance {
+                        target: 3,
+                        success: &[],
+                        failure: &[Effect::HullDamage(1)],
+                    },
+                ],
+            },
+        ],
+    },
+    EventCard {
+        name: "Sick Bay Outbreak",
+        text: "A fever is spreading through the crew quarters.",
+        choices: &[
+            EventChoice {
+                label: "Quarantine the pilots (1 part)",
+                effects: &[
+                    Effect::Parts(-1),
+                    Effect::Chance {
+                        target: 3,
+                        success: &[],
+                        failure: &[Effect::InjurePilots(1)],
+                    },
+                ],
+            },
+            EventChoice {
+                label: "Keep flying",
+                effects: &[Effect::InjurePilots(2)],
+            },
+        ],
+    },
+    EventCard {
+        name: "Friendly Trader",
+        text: "A battered trader hails us, happy to deal with anyone still flying out here.",
+        choices: &[
+            EventChoice {
+                label: "Trade 2 parts for 1 fuel",
+                effects: &[Effect::Parts(-2), Effect::Fuel(1)],
+            },
+            EventChoice {
+                label: "Trade 1 fuel for 2 parts",
+                effects: &[Effect::Fuel(-1), Effect::Parts(2)],
+            },
+            EventChoice {
+                label: "Wave them off",
+                effects: &[],
+            },
+        ],
+    },
+];
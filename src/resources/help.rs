@@ -1,2 +1,2 @@
 /// string that contains all text for the Help tab main block
-pub const HELP_STR: &str = "For information about the game's rules please visit wmakers.net/lostship.\n\nUse <1-7> number keys to change tabs.  Press <Q> to quit at any time.";
+pub const HELP_STR: &str = "For information about the game's rules please visit wmakers.net/lostship.\n\nUse <1-9> number keys to change tabs.  Press <Q> to quit at any time.  Press <D> to show every roll made this leap, with its dice, modifiers and result - past leaps keep theirs in the Log tab.\n\nThe Map tab shows the sector and the two or three systems in reach for the next jump.  Pick one with <Up>/<Down> before leaping.  Each system nudges the threat and scan rolls, and how much of that the readings reveal depends on the sensors.\n\nSome systems have a trading outpost - its beacon shows on the Map tab whatever state the sensors are in.  Once the scan is done, the Outpost tab lets you spend parts on fuel, hire a veteran pilot or have the dock crews patch the hull or overhaul a damaged subsystem.  Pick a trade with <Up>/<Down> and make it with <Enter>.  Every outpost sets its own prices and only has so much to go around.\n\nOn a 5+ after each leap something turns up: a distress call, derelict colony pods, a micrometeorite shower, an outbreak in the sick bay or a friendly trader.  Pick a response with <Up>/<Down> and confirm with <Enter> on the Status tab - threats can't be assessed until it's dealt with.  Greyed out choices cost more fuel or parts than you have.  Every event is recorded in the leap's log.\n\nParts come in five kinds, listed in the Status tab's inventory: hull plating for the hull, engine coils for the engines, optics for the mining laser and sensors, avionics for the scout bay and the scouts, and medical supplies for the sick bay.  What you salvage depends on what you shot down - bombers are mostly plating, interceptors mostly coils, jammers optics and avionics.  Repairs and upgrades need the matching kind, but when you're short any two other parts can stand in for each missing one.  Outposts and events take parts of any kind.\n\nEvery subsystem has three upgrade tiers, bought with <U> on the Status tab during upkeep for 4, 6 and then 8 of its parts.  Each tier adds 1 to what an upgrade does: more hull, cheaper emergency jumps, better mining laser rolls, cheaper recalls, injured pilots recovering in the sick bay each upkeep, and sharper sensor readings.  The mining laser also earns a tier for every 5 kill points it racks up.  The Upgrade panel shows the selected subsystem's tier, the next tier's cost and what it does.\n\nDuring upkeep, <R> on the Status or Hangar tab opens the repair planner.  It lists every point of hull damage, damaged subsystem and damaged scout, plus the next upgrade tier for each system, with what each one costs.  Queue jobs with <Space> or press <G> for a suggested plan that fixes as much as your parts allow.  The planner shows what the queue leaves you with, and <Enter> carries out the whole plan at once - or nothing, if the parts won't stretch or any job on it can't be done.  <Esc> closes it without spending anything.\n\nDecisions that don't roll any dice can be taken back: renames, formation and crew changes, upgrades, the repair plan and outpost trades.  <Z> undoes the last one and <Y> redoes it.  The history is cleared as soon as dice are rolled or the phase moves on, so nothing a roll decided can be undone.\n\nIn combat, <C> recalls the selected scout to the scout bay for the rest of the fight.  It costs the scout's turn and an avionics part (two if the bay is barely functioning, one less per scout bay upgrade tier), and enemy fire aimed at its slot hits the hull instead.  Inoperable scouts return on their own.\n\nFrom round 2, <J> makes an emergency jump straight to the next system - press it once to see the fuel cost, the scouts left behind and the pilots lost with them, and again to jump.  It burns the leap's fuel plus 1 extra (more with damaged engines, one less per engine upgrade tier), scouts still out are lost with their pilots, and the wreckage is forfeit.  The jump counts as a leap, and since the fight wasn't seen through it doesn't reset the leaps since incident.\n\nDeep into a run you may meet more than fighters: Bombers only attack the colony ship hull, Carriers launch a MK1 every round, Interceptors always go after the lead scout, and Jammers keep the mining laser from firing until they are destroyed.\n\nSurvive long enough and a capital ship will find you: it turns up on a set leap (15 on normal), or sooner on a huge threat roll, and keeps coming until it's destroyed - the forecast shows which leap and which roll.  Its guns and engines can't be hit until its shields are down, it launches fighters once its guns are silenced, and it never runs out of fuel.  Destroying it yields a huge haul of parts and a free upgrade tier for your least upgraded system.\n\nEnemies you don't finish off may follow you.  A fighter that breaks off for fuel follows on a 5+, one you jump away from on a 4+.  Each pursuer adds +1 to the next threat roll and joins the next fight with the hp it had left - the Status tab shows who is on your trail.  With pursuers about, a clear threat roll still means a fight with them, and the forecast counts it that way.\n\nThe threat and scan rolls get riskier the longer it has been since the last incident.  Once a fight is over, with every enemy destroyed or broken off, the count starts again at the next leap.\n\nEvery campaign is recorded as a replay - its seed and every key pressed - and saved to the replays folder in your config directory (e.g. ~/.config/lostship/replays) when you quit, named after the seed - a second campaign on the same seed is saved alongside the first as <seed>-2.replay and so on.  Run lostship replay <file> to watch it back with <Space> to play or pause, <Right> to step one command at a time and <+>/<-> to change the speed.  A replay doubles as a save: lostship play --load <file> plays it back in an instant and carries on from there.\n\nlostship play also takes --seed <n> to pick the campaign, --difficulty easy|normal|hard, --rules <file> to change the campaign settings and --no-color.  Outside the game, lostship sim plays batches of campaigns on autopilot, lostship export <save> --format md|csv|json prints a campaign's leap log and lostship validate <save> checks a save still loads.  lostship bot plays over stdin and stdout for programs instead of people: send one JSON command per line, such as {\"cmd\":\"attack\",\"scout\":0,\"target\":1}, and every reply is a JSON line with the game state and the legal actions, or an error with a code saying why the command was refused.  lostship help lists every option.\n\nPilot and scout names are generated from built in lists.  To add your own, create lostship/names.txt in your config directory (e.g. ~/.config/lostship/names.txt) with one name per line under [callsigns], [surnames], [prefixes] or [scouts] headings.  Replays keep a copy of your lists, so they deal the same names on any machine.";
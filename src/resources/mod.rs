@@ -1,2 +1,8 @@
 pub mod about;
+pub mod events;
 pub mod help;
+pub mod names;
+pub mod pilot_names;
+pub mod scout_names;
+pub mod ship_names;
+pub mod star_names;
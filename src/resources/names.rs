@@ -0,0 +1,182 @@
+use std::{collections::HashSet, env, fs, path::PathBuf};
+
+use rand::seq::SliceRandom;
+
+use crate::gamerules::name_rng;
+
+use super::{
+    pilot_names::{CALLSIGNS, SURNAMES},
+    scout_names::SCOUT_NAMES,
+    ship_names::SHIP_PREFIXES,
+};
+
+/// name of the optional user name list inside the config directory
+const NAMES_FILE: &str = "names.txt";
+/// how many random combinations to try before falling back to numbering a name
+const MAX_ATTEMPTS: usize = 64;
+
+/// builds unique pilot and ship names for a campaign
+/// pilots are "Callsign" Surname, ships are Prefix Name
+/// every name handed out (or registered) is remembered so a campaign never repeats one
+#[derive(Debug, Clone)]
+pub struct NameGenerator {
+    pub callsigns: Vec<String>,
+    pub surnames: Vec<String>,
+    pub ship_prefixes: Vec<String>,
+    pub scout_names: Vec<String>,
+    used: HashSet<String>,
+}
+
+impl NameGenerator {
+    /// only the name pools compiled into the game
+    pub fn builtin() -> Self {
+        NameGenerator {
+            callsigns: CALLSIGNS.iter().map(|x| x.to_string()).collect(),
+            surnames: SURNAMES.iter().map(|x| x.to_string()).collect(),
+            ship_prefixes: SHIP_PREFIXES.iter().map(|x| x.to_string()).collect(),
+            scout_names: SCOUT_NAMES.iter().map(|x| x.to_string()).collect(),
+            used: HashSet::new(),
+        }
+    }
+
+    /// built in name pools plus the names in a user list
+    pub fn with_lists(contents: &str) -> Self {
+        let mut generator = NameGenerator::builtin();
+        generator.add_lists(contents);
+        generator
+    }
+
+    /// adds names from a user list to the pools
+    /// the list is split into [callsigns], [surnames], [prefixes] and [scouts] sections with one
+    /// name per line, blank lines and lines starting with # are ignored
+    pub fn add_lists(&mut self, contents: &str) {
+        let mut section: Option<&mut Vec<String>> = None;
+        for line in list_lines(contents) {
+            if line.starts_with('[') && line.ends_with(']') {
+                section = match line[1..line.len() - 1].trim().to_lowercase().as_str() {
+                    "callsigns" => Some(&mut self.callsigns),
+                    "surnames" => Some(&mut self.surnames),
+                    "prefixes" => Some(&mut self.ship_prefixes),
+                    "scouts" => Some(&mut self.scout_names),
+                    _ => None,
+                };
+                continue;
+            }
+            if let Some(pool) = section.as_mut() {
+                if !pool.iter().any(|x| x == line) {
+                    pool.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    /// returns a pilot name that hasn't been used yet this campaign
+    pub fn pilot_name(&mut self) -> String {
+        unique_name(&mut self.used, &self.callsigns, &self.surnames, |a, b| {
+            format!("\"{a}\" {b}")
+        })
+    }
+
+    /// returns a scout ship name that hasn't been used yet this campaign
+    pub fn ship_name(&mut self) -> String {
+        unique_name(
+            &mut self.used,
+            &self.ship_prefixes,
+            &self.scout_names,
+            |a, b| format!("{a} {b}"),
+        )
+    }
+
+    /// marks a name (e.g. one typed in by the player) as taken
+    pub fn register(&mut self, name: &str) {
+        self.used.insert(name.to_string());
+    }
+}
+
+/// combines a random element from each pool until it finds an unused name
+/// once the pools are exhausted the name gets a roman numeral suffix instead
+fn unique_name(
+    used: &mut HashSet<String>,
+    first: &[String],
+    second: &[String],
+    combine: impl Fn(&str, &str) -> String,
+) -> String {
+    let mut rng = name_rng();
+    let mut name = String::new();
+    for _ in 0..MAX_ATTEMPTS {
+        let a = first.choose(&mut rng).map_or("Unknown", |x| x.as_str());
+        let b = second.choose(&mut rng).map_or("Unknown", |x| x.as_str());
+        name = combine(a, b);
+        if !used.contains(&name) {
+            used.insert(name.clone());
+            return name;
+        }
+    }
+    let mut numeral = 2;
+    while used.contains(&format!("{name} {}", roman(numeral))) {
+        numeral += 1;
+    }
+    let name = format!("{name} {}", roman(numeral));
+    used.insert(name.clone());
+    name
+}
+
+/// the game's config directory
+/// $XDG_CONFIG_HOME/lostship, falling back to ~/.config/lostship (or %APPDATA%\lostship)
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(config_dir.join("lostship"))
+}
+
+/// location of the user's names file
+pub fn names_file_path() -> Option<PathBuf> {
+    Some(config_dir()?.join(NAMES_FILE))
+}
+
+/// the user's names file with comments and blank lines dropped, empty if there isn't one
+/// replays keep a copy so they deal the same names on a machine without the file
+pub fn user_lists() -> String {
+    let contents = names_file_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    list_lines(&contents).join("\n")
+}
+
+/// the lines of a names list that mean something - headings and names
+pub fn list_lines(contents: &str) -> Vec<&str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|x| !x.is_empty() && !x.starts_with('#'))
+        .collect()
+}
+
+/// roman numerals for name suffixes (II, III, IV...)
+fn roman(mut n: u64) -> String {
+    let table = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut result = String::new();
+    for (value, numeral) in table {
+        while n >= value {
+            result += numeral;
+            n -= value;
+        }
+    }
+    result
+}
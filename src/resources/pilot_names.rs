@@ -0,0 +1,59 @@
+/// callsigns used for the first half of generated pilot names
+pub const CALLSIGNS: [&str; 40] = [
+    "Ace", "Banshee", "Blaze", "Bishop", "Comet", "Cricket", "Dagger", "Doc", "Echo", "Ember",
+    "Flint", "Ghost", "Gizmo", "Halo", "Hex", "Jinx", "Jolt", "Kite", "Lark", "Lucky", "Maverick",
+    "Moth", "Nova", "Onyx", "Patch", "Pike", "Quill", "Raven", "Rook", "Rusty", "Sable", "Sparrow",
+    "Spud", "Tango", "Thistle", "Vesper", "Viper", "Wren", "Yankee", "Zero",
+];
+
+/// surnames used for the second half of generated pilot names
+pub const SURNAMES: [&str; 48] = [
+    "Abara",
+    "Achebe",
+    "Baptiste",
+    "Becker",
+    "Castillo",
+    "Chen",
+    "Dalca",
+    "Demir",
+    "Eriksen",
+    "Esposito",
+    "Farrow",
+    "Fujita",
+    "Garza",
+    "Haddad",
+    "Hale",
+    "Ibarra",
+    "Ivanova",
+    "Jensen",
+    "Kaur",
+    "Kowalski",
+    "Lindqvist",
+    "Lopez",
+    "Mbeki",
+    "Moreau",
+    "Nakamura",
+    "Novak",
+    "Obi",
+    "Okafor",
+    "Park",
+    "Petrov",
+    "Quinn",
+    "Rahman",
+    "Reyes",
+    "Sato",
+    "Silva",
+    "Sorensen",
+    "Tanaka",
+    "Torres",
+    "Ulloa",
+    "Varga",
+    "Vance",
+    "Walsh",
+    "Weber",
+    "Xu",
+    "Yilmaz",
+    "Young",
+    "Zhang",
+    "Zeller",
+];
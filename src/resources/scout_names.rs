@@ -0,0 +1,43 @@
+/// names used for the second half of generated scout ship names
+pub const SCOUT_NAMES: [&str; 40] = [
+    "Aurora",
+    "Beacon",
+    "Bramble",
+    "Cinder",
+    "Comet",
+    "Dawn",
+    "Drifter",
+    "Dusk",
+    "Ember",
+    "Farsight",
+    "Firefly",
+    "Gale",
+    "Glimmer",
+    "Harbinger",
+    "Hearth",
+    "Horizon",
+    "Juniper",
+    "Lantern",
+    "Meridian",
+    "Mistral",
+    "Nimbus",
+    "Northstar",
+    "Pathfinder",
+    "Pilgrim",
+    "Quasar",
+    "Radiant",
+    "Rambler",
+    "Resolve",
+    "Seeker",
+    "Solace",
+    "Sparrowhawk",
+    "Starling",
+    "Tempest",
+    "Tidings",
+    "Valiant",
+    "Vigil",
+    "Wanderer",
+    "Wayfarer",
+    "Whisper",
+    "Zephyr",
+];
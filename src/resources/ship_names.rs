@@ -0,0 +1,19 @@
+/// class prefixes used for the first half of generated scout ship names
+pub const SHIP_PREFIXES: [&str; 16] = [
+    "Cormorant",
+    "Curlew",
+    "Dart",
+    "Falcon",
+    "Gannet",
+    "Harrier",
+    "Heron",
+    "Kestrel",
+    "Lancer",
+    "Merlin",
+    "Osprey",
+    "Petrel",
+    "Skua",
+    "Swift",
+    "Tern",
+    "Wasp",
+];
@@ -0,0 +1,27 @@
+/// catalogue names used for the systems on the sector map, a number is added to each
+pub const STAR_NAMES: [&str; 24] = [
+    "Achird",
+    "Alcor",
+    "Ankaa",
+    "Barnard",
+    "Castor",
+    "Deneb",
+    "Electra",
+    "Fomalhaut",
+    "Gliese",
+    "Hadar",
+    "Izar",
+    "Kepler",
+    "Lalande",
+    "Merak",
+    "Mizar",
+    "Nashira",
+    "Ogma",
+    "Procyon",
+    "Rigel",
+    "Sabik",
+    "Tarazed",
+    "Vega",
+    "Wolf",
+    "Zosma",
+];
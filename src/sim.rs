@@ -0,0 +1,108 @@
+use crate::{
+    app::App,
+    gamerules::{
+        combat::{auto_resolve, AutoPolicy},
+        events::resolve_event,
+        pilot::PilotStatus,
+        planner::{apply_plan, RepairPlan},
+        ship::max_hull,
+        turn::{advance, Phase},
+    },
+};
+
+/// NOTE: guards against a campaign that stops making progress, no real one comes close to this
+const MAX_STEPS: usize = 100_000;
+
+/// how a headless campaign went
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub seed: u64,
+    pub leaps: usize,
+    pub fuel: u64,
+    pub parts: u64,
+    pub hull_damage: u64,
+    pub pilots_lost: usize,
+    pub kills: usize,
+    pub boss_defeated: bool,
+    pub ending: String,
+}
+
+impl Outcome {
+    fn new(app: &App, ending: String) -> Self {
+        Outcome {
+            seed: app.recording.seed,
+            leaps: app.log.len(),
+            fuel: app.fuel,
+            parts: app.parts.total(),
+            hull_damage: app.hull_damage,
+            pilots_lost: app
+                .roster
+                .pilots
+                .iter()
+                .filter(|x| x.status == PilotStatus::Kia)
+                .count(),
+            kills: app.log.iter().map(|x| x.kills.len()).sum(),
+            boss_defeated: app.boss_defeated,
+            ending,
+        }
+    }
+}
+
+/// why the campaign can't go on, None while it still can
+pub fn game_over(app: &App) -> Option<String> {
+    if app.hull_damage > max_hull(app.hull_upgrade) {
+        Some("hull breached".to_string())
+    } else if app
+        .roster
+        .pilots
+        .iter()
+        .all(|x| x.status == PilotStatus::Kia)
+    {
+        Some("every pilot lost".to_string())
+    } else if app.phase == Phase::Leap && app.fuel == 0 {
+        Some("out of fuel".to_string())
+    } else {
+        None
+    }
+}
+
+/// plays the campaign with nobody at the keys until it ends or has logged `max_leaps` leaps
+/// fights are auto-resolved with `policy`, events take the first choice that can be paid for
+/// and every upkeep carries out the repair planner's suggested plan
+pub fn play_out(app: &mut App, max_leaps: usize, policy: AutoPolicy) -> Outcome {
+    for _ in 0..MAX_STEPS {
+        if let Some(ending) = game_over(app) {
+            return Outcome::new(app, ending);
+        }
+        if app.log.len() >= max_leaps {
+            return Outcome::new(app, "leap limit".to_string());
+        }
+        match app.phase {
+            Phase::Combat if app.in_combat => {
+                auto_resolve(app, policy, false);
+                continue;
+            }
+            Phase::AssessThreat => {
+                if let Some(mut event) = app.event {
+                    for choice in 0..event.card.choices.len() {
+                        event.selected = choice;
+                        app.event = Some(event);
+                        if resolve_event(app).is_ok() {
+                            break;
+                        }
+                    }
+                }
+            }
+            Phase::Upkeep => {
+                let mut plan = RepairPlan::new(app);
+                plan.suggest(&app.parts);
+                let _ = apply_plan(app, &plan); // nothing to repair is fine
+            }
+            _ => {}
+        }
+        if let Err(reason) = advance(app) {
+            return Outcome::new(app, format!("stuck: {reason}"));
+        }
+    }
+    Outcome::new(app, "step limit".to_string())
+}
@@ -9,7 +9,9 @@ use ratatui::{
     symbols::border,
     widgets::{
         block::{Block, Position, Title},
-        Borders, Cell, List, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table, Tabs, Wrap,
+        canvas::{Canvas, Line as CanvasLine},
+        Borders, Cell, Clear, LineGauge, List, ListState, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, Table, Tabs, Wrap,
     },
 };
 use std::io::{self, stdout, Stdout};
@@ -17,14 +19,34 @@ use std::io::{self, stdout, Stdout};
 use crate::{
     app::App,
     gamerules::{
-        combat::combat_to_app,
-        pilot::{PilotStatus, Rank},
-        ship::ShipDamage,
+        combat::{recall_cost, Combat},
+        game_functions::{
+            emergency_jump_cost, next_leaps_since_incident, threat_group, threat_roll_modifiers,
+            threats_summary,
+        },
+        odds::{
+            attack_odds, encounter_forecast, forecast_detail, kill_chance, laser_expected_damage,
+            percent, scan_forecast, threat_forecast, EncounterClass,
+        },
+        outpost::offers,
+        parts::Part,
+        pilot::{Pilot, PilotStatus, Rank},
+        planner::RepairPlan,
+        sector,
+        ship::{
+            tier_cost, tier_effect, upgrade_tier, ShipDamage, LASER_KILLS_PER_TIER, MAX_TIER,
+            SUBSYSTEM_NAMES,
+        },
+        threat::FighterState,
+        turn::Phase,
+        ScanResult,
     },
     resources::{about::ABOUT_STR, help::HELP_STR},
 };
 
-use super::status::{get_fuel_string, get_hull_string, get_parts_string, get_subsys_string};
+use super::status::{
+    get_fuel_string, get_hull_string, get_parts_string, get_pursuers_string, get_subsys_string,
+};
 
 /// a type alias for the terminal type used
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
@@ -38,6 +60,8 @@ pub enum MenuTabs {
     Hangar,
     Crew,
     Combat,
+    Map,
+    Outpost,
     About,
     Help,
 }
@@ -74,8 +98,10 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
         "3. Hangar",
         "4. Crew",
         "5. Combat",
-        "6. About",
-        "7. Help",
+        "6. Map",
+        "7. Outpost",
+        "8. About",
+        "9. Help",
     ])
     .block(Block::default().title("Menu").borders(Borders::ALL))
     .style(Style::default().white())
@@ -110,10 +136,16 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
             instructions_text = Text::from(vec![Line::from(vec![
                 "<Q>".yellow().bold(),
                 " Quit ".into(),
+                "<N>".yellow().bold(),
+                " Next phase ".into(),
                 "<Up>/<Down>".yellow().bold(),
                 " Change selection. ".into(),
                 "<R>".yellow().bold(),
-                " Repair ".into(),
+                " Repair Planner ".into(),
+                "<Z>/<Y>".yellow().bold(),
+                undo_hint(app).into(),
+                "<D>".yellow().bold(),
+                " Dice ".into(),
             ])]);
         }
         MenuTabs::Log => {
@@ -135,11 +167,13 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
                 "<E>".yellow().bold(),
                 " Edit ".into(),
                 "<W>/<S>".yellow().bold(),
-                " Shift Assignment ".into(),
+                " Move Position ".into(),
                 "<R>".yellow().bold(),
-                " Repair ".into(),
+                " Repair Planner ".into(),
                 "<U>".yellow().bold(),
                 " Upgrade ".into(),
+                "<Z>/<Y>".yellow().bold(),
+                undo_hint(app).into(),
             ])]);
         }
         MenuTabs::Crew => {
@@ -153,6 +187,8 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
                 " Edit ".into(),
                 "<W>/<S>".yellow().bold(),
                 " Shift Assignment ".into(),
+                "<Z>/<Y>".yellow().bold(),
+                undo_hint(app).into(),
             ])]);
         }
         MenuTabs::Combat => {
@@ -168,6 +204,42 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
                 " Scout Attack ".into(),
                 "<M>".yellow().bold(),
                 " Mining Laser ".into(),
+                "<C>".yellow().bold(),
+                " Recall ".into(),
+                "<J>".yellow().bold(),
+                " Emergency Jump ".into(),
+                "<N>".yellow().bold(),
+                " Enemy Turn ".into(),
+                "<X>".yellow().bold(),
+                format!(" Auto-resolve ({}) ", app.auto_policy).into(),
+                "<P>".yellow().bold(),
+                " Policy ".into(),
+                "<L>".yellow().bold(),
+                " Log ".into(),
+                "<D>".yellow().bold(),
+                " Dice ".into(),
+            ])]);
+        }
+        MenuTabs::Map => {
+            draw_main_map_tab(app, frame, chunks[1], main_block);
+            instructions_text = Text::from(vec![Line::from(vec![
+                "<Q>".yellow().bold(),
+                " Quit ".into(),
+                "<Up>/<Down>".yellow().bold(),
+                " Choose destination ".into(),
+                "<D>".yellow().bold(),
+                " Dice ".into(),
+            ])]);
+        }
+        MenuTabs::Outpost => {
+            draw_main_outpost_tab(app, frame, chunks[1], main_block);
+            instructions_text = Text::from(vec![Line::from(vec![
+                "<Q>".yellow().bold(),
+                " Quit ".into(),
+                "<Up>/<Down>".yellow().bold(),
+                " Change selection ".into(),
+                "<Enter>".yellow().bold(),
+                " Trade ".into(),
             ])]);
         }
         MenuTabs::About => {
@@ -178,12 +250,40 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
             instructions_text = Text::from(vec![Line::from(vec![
                 "<Q>".yellow().bold(),
                 " Quit ".into(),
-                "<1-7>".yellow().bold(),
+                "<1-9>".yellow().bold(),
                 " Change Tab".into(),
             ])]);
         }
     };
 
+    // replays take over the keys, the tabs follow whatever the recording does
+    if let Some(playback) = &app.playback {
+        instructions_text = Text::from(vec![Line::from(vec![
+            format!(
+                " REPLAY {}/{} ",
+                playback.position,
+                playback.replay.commands.len()
+            )
+            .cyan()
+            .bold(),
+            if playback.finished() {
+                " Finished ".green()
+            } else if playback.playing {
+                " Playing ".green()
+            } else {
+                " Paused ".yellow()
+            },
+            "<Space>".yellow().bold(),
+            " Play/Pause ".into(),
+            "<Right>".yellow().bold(),
+            " Step ".into(),
+            "<+>/<->".yellow().bold(),
+            format!(" Speed ({}ms) ", playback.delay).into(),
+            "<Q>".yellow().bold(),
+            " Quit ".into(),
+        ])]);
+    }
+
     let instructions = Paragraph::new(instructions_text)
         .centered()
         .block(instructions_block);
@@ -192,6 +292,26 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     frame.render_widget(tabs, chunks[0]);
     frame.render_widget(instructions, chunks[2]);
 
+    // draw pending event card
+    if app.active_tab == MenuTabs::Status && app.event.is_some() {
+        draw_event_panel(app, frame);
+    }
+
+    // draw repair planner
+    if let Some(plan) = &app.planner {
+        draw_planner_panel(app, plan, frame);
+    }
+
+    // draw combat event log
+    if app.show_combat_log {
+        draw_combat_log_panel(app, frame);
+    }
+
+    // draw dice history panel
+    if app.show_dice {
+        draw_dice_panel(app, frame);
+    }
+
     // draw editing popup
     if app.editing {
         let popup_block = Block::default()
@@ -204,14 +324,188 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     }
 }
 
+/// undo/redo key hint with how many steps each way are available
+fn undo_hint(app: &App) -> String {
+    let (undo, redo) = app.history.steps();
+    format!(" Undo/Redo ({undo}/{redo}) ")
+}
+
+/// draws the combat event log popup - everything that has happened in the current fight
+fn draw_combat_log_panel(app: &App, frame: &mut Frame) {
+    let popup_area = centered_rect(frame.size(), 80, 60);
+    let popup_block = Block::default()
+        .title(" Combat Log ")
+        .title(
+            Title::from(" <L> Close ")
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    let visible = popup_block.inner(popup_area).height as usize;
+    let lines: Vec<Line> = match &app.combat {
+        Some(combat) if !combat.log.is_empty() => combat
+            .log
+            .iter()
+            .skip(combat.log.len().saturating_sub(visible))
+            .map(|x| Line::from(x.as_str()))
+            .collect(),
+        _ => vec!["Nothing has happened yet.".into()],
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(popup_block),
+        popup_area,
+    );
+}
+
+/// draws the event card popup - the event text and its choices, with what each one costs
+fn draw_event_panel(app: &App, frame: &mut Frame) {
+    let Some(event) = app.event else {
+        return;
+    };
+    let popup_area = centered_rect(frame.size(), 60, 40);
+    let popup_block = Block::default()
+        .title(format!(" {} ", event.card.name))
+        .title(
+            Title::from(" <Up>/<Down> Choose  <Enter> Confirm ")
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    let mut lines = vec![Line::from(event.card.text), Line::from("")];
+    for (i, choice) in event.card.choices.iter().enumerate() {
+        let (fuel, parts) = choice.cost();
+        let line = if i == event.selected {
+            Line::from(format!(">> {}", choice.label)).reversed()
+        } else {
+            Line::from(format!("   {}", choice.label))
+        };
+        if app.fuel < fuel || app.parts.total() < parts {
+            lines.push(line.dark_gray());
+        } else {
+            lines.push(line);
+        }
+    }
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(popup_block),
+        popup_area,
+    );
+}
+
+/// draws the repair planner popup - every job on offer with its cost, the queue and what it
+/// leaves in the parts bins
+fn draw_planner_panel(app: &App, plan: &RepairPlan, frame: &mut Frame) {
+    let popup_area = centered_rect(frame.size(), 70, 70);
+    let popup_block = Block::default()
+        .title(" Repair Planner ")
+        .title(
+            Title::from(" <Space> Queue  <G> Suggest  <Enter> Apply  <Esc> Close ")
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    let inner_area = popup_block.inner(popup_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(4)])
+        .split(inner_area);
+
+    let items: Vec<Line> = if plan.entries.is_empty() {
+        vec!["Nothing to repair or upgrade.".into()]
+    } else {
+        plan.entries
+            .iter()
+            .map(|x| {
+                let cost = if x.cost == 0 {
+                    "free".to_string()
+                } else {
+                    format!("{} {}", x.cost, x.part)
+                };
+                let line = Line::from(format!(
+                    "[{}] {} - {cost}",
+                    if x.queued { "x" } else { " " },
+                    x.label
+                ));
+                if x.queued {
+                    line.cyan()
+                } else {
+                    line
+                }
+            })
+            .collect()
+    };
+    let mut state = ListState::default().with_selected(Some(plan.selected));
+    let list = List::new(items)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">>");
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+
+    let budget = match plan.budget(&app.parts) {
+        Ok(left) => Line::from(vec!["Left after plan: ".into(), left.to_string().green()]),
+        Err(reason) => Line::from(reason).red(),
+    };
+    let summary = Text::from(vec![
+        Line::from(vec!["On hand: ".into(), app.parts.to_string().into()]),
+        budget,
+    ]);
+    frame.render_widget(
+        Paragraph::new(summary)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::TOP)),
+        chunks[1],
+    );
+}
+
+/// draws the dice history popup - every roll made during the current leap, newest at the bottom
+fn draw_dice_panel(app: &App, frame: &mut Frame) {
+    let popup_area = centered_rect(frame.size(), 80, 60);
+    let popup_block = Block::default()
+        .title(format!(" Dice History - Leap {} ", app.current_leap.number))
+        .title(
+            Title::from(" <D> Close ")
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    let visible = popup_block.inner(popup_area).height as usize;
+    let rolls = &app.current_leap.rolls;
+    let lines: Vec<Line> = if rolls.is_empty() {
+        vec!["No rolls yet this leap.".into()]
+    } else {
+        rolls
+            .iter()
+            .skip(rolls.len().saturating_sub(visible))
+            .map(|x| x.to_line())
+            .collect()
+    };
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(Paragraph::new(lines).block(popup_block), popup_area);
+}
+
 /// draws center chunk of Status tab
 fn draw_main_status_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
     let inner_area = main_block.inner(chunk);
     main_block.render(chunk, frame.buffer_mut());
+    let phase_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(inner_area);
+    draw_phase_progress(app, frame, phase_chunks[0]);
     let sub_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
-        .split(inner_area);
+        .split(phase_chunks[1]);
 
     // status, left section
     // TODO: change color based on number, status
@@ -220,8 +514,12 @@ fn draw_main_status_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
             "LEAPS SINCE INCIDENT: ".into(),
             app.leaps_since_incident.to_string().into(),
         ]),
+        Line::from(vec![
+            "Pursuers: ".into(),
+            get_pursuers_string(&app.pursuers),
+        ]),
         Line::from(vec!["Fuel: ".into(), get_fuel_string(&app.fuel)]),
-        Line::from(vec!["Parts: ".into(), get_parts_string(&app.parts)]),
+        Line::from(vec!["Parts: ".into(), get_parts_string(app.parts.total())]),
         Line::from(vec![
             "Hull Damage: ".into(),
             get_hull_string(app.hull_damage, app.hull_upgrade),
@@ -230,7 +528,7 @@ fn draw_main_status_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
         Line::from(vec![
             "Mining Laser: ".into(),
             get_subsys_string(&app.mining_laser),
-            format!(" ({} kills)", app.laser_kills).into(),
+            laser_kills_text(app).into(),
         ]),
         Line::from(vec![
             "Scout Bay: ".into(),
@@ -244,19 +542,270 @@ fn draw_main_status_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
     frame.render_widget(main_thing, sub_chunks[0]);
 
     // sub system list, right section
-    let list_items = [
-        "Hull",
-        "Engines",
-        "Mining Laser",
-        "Scout Bay",
-        "Sick Bay",
-        "Sensors",
-    ];
+    let list_items: Vec<String> = SUBSYSTEM_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| format!("{name} [T{}]", upgrade_tier(app, i)))
+        .collect();
     let list = List::new(list_items)
         .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
         .highlight_symbol(">>")
         .repeat_highlight_symbol(true);
-    frame.render_stateful_widget(list, sub_chunks[1], &mut app.subsys_list_state);
+    let right_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Min(3),
+        ])
+        .split(sub_chunks[1]);
+    frame.render_stateful_widget(list, right_chunks[0], &mut app.subsys_list_state);
+    let upgrades = Paragraph::new(upgrade_text(app))
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title(" Upgrade ").borders(Borders::TOP));
+    frame.render_widget(upgrades, right_chunks[1]);
+    let inventory = Paragraph::new(inventory_text(app))
+        .block(Block::default().title(" Inventory ").borders(Borders::TOP));
+    frame.render_widget(inventory, right_chunks[2]);
+    let forecast = Paragraph::new(forecast_text(app))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(" Leap Forecast ")
+                .borders(Borders::TOP),
+        );
+    frame.render_widget(forecast, right_chunks[3]);
+}
+
+/// mining laser kill record and how far it is from earning the next tier
+fn laser_kills_text(app: &App) -> String {
+    let next = LASER_KILLS_PER_TIER * (u64::from(app.mining_laser.upgrade) + 1);
+    if app.mining_laser.upgrade < MAX_TIER && app.laser_kills < next {
+        format!(
+            " ({} kills, tier {} at {next})",
+            app.laser_kills,
+            app.mining_laser.upgrade + 1
+        )
+    } else {
+        format!(" ({} kills)", app.laser_kills)
+    }
+}
+
+/// selected subsystem's current tier and effect, and what the next tier costs and does
+fn upgrade_text<'a>(app: &App) -> Text<'a> {
+    let Some(subsystem) = app.subsys_list_state.selected() else {
+        return Text::from("Select a subsystem to see its upgrades.");
+    };
+    let tier = upgrade_tier(app, subsystem);
+    let current = Line::from(format!(
+        "Tier {tier}/{MAX_TIER}: {}",
+        tier_effect(subsystem, tier)
+    ));
+    let next = match tier_cost(tier) {
+        Some(cost) => Line::from(vec![
+            format!("Next: {cost} {} - ", Part::for_subsystem(subsystem)).yellow(),
+            tier_effect(subsystem, tier + 1).into(),
+        ]),
+        None => "Fully upgraded".green().into(),
+    };
+    Text::from(vec![current, next])
+}
+
+/// parts on hand by kind, and what the selected subsystem's repairs and upgrades take from them
+fn inventory_text<'a>(app: &App) -> Text<'a> {
+    let wanted = app.subsys_list_state.selected().map(Part::for_subsystem);
+    let lines: Vec<Line> = Part::ALL
+        .iter()
+        .map(|part| {
+            let count = app.parts.count(*part);
+            let line = Line::from(vec![
+                format!("{part}: ").into(),
+                if count == 0 {
+                    "0".red()
+                } else {
+                    count.to_string().into()
+                },
+            ]);
+            if wanted == Some(*part) {
+                line.bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+    Text::from(lines)
+}
+
+/// progress bar of the leap phases - done phases green, the current one highlighted
+fn draw_phase_progress(app: &App, frame: &mut Frame, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+    let current = app.phase.index();
+    let mut spans: Vec<Span> = Vec::new();
+    for (i, phase) in Phase::ALL.iter().enumerate() {
+        if i > 0 {
+            spans.push(" > ".dark_gray());
+        }
+        let label = format!(" {phase} ");
+        spans.push(if i < current {
+            label.green()
+        } else if i == current {
+            label.black().on_yellow().bold()
+        } else {
+            label.dark_gray()
+        });
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+    let gauge = LineGauge::default()
+        .ratio(current as f64 / Phase::ALL.len() as f64)
+        .gauge_style(Style::default().yellow())
+        .line_set(symbols::line::THICK);
+    frame.render_widget(gauge, chunks[1]);
+    let exit = Line::from(vec![
+        "To continue: ".cyan(),
+        app.phase.exit_condition().into(),
+    ]);
+    frame.render_widget(Paragraph::new(exit), chunks[2]);
+}
+
+/// threat and scan odds for the upcoming rolls, limited by what the sensors can make out
+fn forecast_text<'a>(app: &App) -> Text<'a> {
+    // the leap counter only changes in step 1, so rolls still to come this leap use the current value
+    let threat_leaps = if app.phase == Phase::AssessThreat {
+        app.leaps_since_incident
+    } else {
+        next_leaps_since_incident(app)
+    };
+    let scan_now = matches!(
+        app.phase,
+        Phase::AssessThreat | Phase::Combat | Phase::Salvage | Phase::Scan
+    );
+    let scan_leaps = if scan_now {
+        app.leaps_since_incident
+    } else {
+        next_leaps_since_incident(app)
+    };
+    let detail = forecast_detail(&app.sensors);
+    // rolls in the system we're in use its modifiers, rolls after the next jump only use the
+    // picked destination's if the sensors can read them exactly
+    let next_system = app.sector.selected().filter(|_| detail >= 4);
+    let threat_system = if app.phase == Phase::AssessThreat {
+        app.sector.current.as_ref()
+    } else {
+        next_system
+    };
+    let scan_system = if scan_now {
+        app.sector.current.as_ref()
+    } else {
+        next_system
+    };
+    let mut lines: Vec<Line> = Vec::new();
+    if detail == 0 {
+        lines.push("Sensors inoperable - no forecast available.".red().into());
+        return Text::from(lines);
+    }
+    lines.push(
+        Line::from(format!(
+            "Threat roll at {threat_leaps} leaps since incident:"
+        ))
+        .cyan(),
+    );
+    let pursuers = app.pursuers.len();
+    if pursuers > 0 {
+        lines.push(
+            format!("  {pursuers} pursuer(s) add +{pursuers} and join any fight")
+                .red()
+                .into(),
+        );
+    }
+    // the capital ship keys off the campaign's leap number as well as the roll
+    let threat_leap = match app.phase {
+        Phase::Leap => app.log.len() as u64 + 1,
+        Phase::AssessThreat => app.current_leap.number,
+        _ => app.current_leap.number + 1,
+    };
+    let (boss_leap, boss_threshold) = (app.rules.boss_after_leaps, app.rules.boss_threshold);
+    if !app.boss_defeated {
+        let trigger = if threat_leap == boss_leap {
+            format!("  Capital ship certain - leap {boss_leap} is its leap")
+        } else if threat_leap < boss_leap {
+            format!("  Capital ship on leap {boss_leap}, or sooner on a roll of {boss_threshold}+")
+        } else {
+            format!("  Capital ship on a roll of {boss_threshold}+")
+        };
+        lines.push(trigger.red().into());
+    }
+    let group = |result| threat_group(&app.rules, app.boss_defeated, threat_leap, result);
+    let threat_modifier: i64 = threat_roll_modifiers(app, threat_leaps, threat_system)
+        .iter()
+        .map(|(_, value)| value)
+        .sum();
+    let scan_modifier = scan_system.map_or(0, |x| x.scan_modifier);
+    let encounters = encounter_forecast(threat_modifier, group);
+    // pursuers turn up whatever the roll says, so a clear roll still means a fight with them
+    let clear_label = if pursuers > 0 {
+        "Pursuers only"
+    } else {
+        "No contact"
+    };
+    if detail == 1 {
+        let clear = encounters
+            .iter()
+            .filter(|(class, _)| *class == EncounterClass::Clear)
+            .map(|(_, chance)| chance)
+            .sum::<f64>();
+        if pursuers > 0 {
+            lines.push(format!("  Pursuers only {}", percent(clear)).into());
+            lines.push(format!("  More contacts {}", percent(1.0 - clear)).into());
+        } else {
+            lines.push(format!("  Sector clear {}", percent(clear)).into());
+            lines.push(format!("  Enemy contact {}", percent(1.0 - clear)).into());
+        }
+        lines.push(
+            "  (sensors too damaged to read group sizes)"
+                .yellow()
+                .into(),
+        );
+    } else {
+        for (class, chance) in encounters {
+            let label = match class {
+                EncounterClass::Clear => clear_label.to_string(),
+                _ => class.to_string(),
+            };
+            lines.push(format!("  {label}: {}", percent(chance)).into());
+        }
+    }
+    if detail >= 4 {
+        lines.push(Line::from("Exact groups:").cyan());
+        for (threats, chance) in threat_forecast(threat_modifier, group) {
+            let group = match threats {
+                Some(threats) => threats_summary(&threats),
+                None => clear_label.to_lowercase(),
+            };
+            lines.push(format!("  {group}: {}", percent(chance)).into());
+        }
+    }
+    if detail >= 3 {
+        lines.push(Line::from(format!("Scan roll at {scan_leaps} leaps since incident:")).cyan());
+        for ((scan, fuel), chance) in scan_forecast(scan_leaps, scan_modifier) {
+            let label = if scan == ScanResult::Fuel {
+                format!("{scan} +{fuel}")
+            } else {
+                scan.to_string()
+            };
+            lines.push(format!("  {label}: {}", percent(chance)).into());
+        }
+    } else {
+        lines.push("Scan forecast needs healthier sensors.".yellow().into());
+    }
+    Text::from(lines)
 }
 
 fn draw_main_log_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
@@ -265,7 +814,7 @@ fn draw_main_log_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
     // iterate over log and turn each element into a Paragraph
     let mut scroll_content: Vec<Line> = Vec::new();
     for entry in app.log.iter() {
-        scroll_content.append(&mut entry.to_lines());
+        scroll_content.append(&mut entry.to_lines(&app.roster));
     }
     let _ = app.log_scroll_state.content_length(scroll_content.len());
     let scroll_paragraph = Paragraph::new(scroll_content);
@@ -280,40 +829,17 @@ fn draw_main_log_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
 }
 
 fn draw_main_hangar_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
-    // reset pilot information in case order changed
-    for i in 0..app.scouts.len() {
-        app.scouts[i].pilot = app.pilots[i].clone();
-    }
     let header_row = Row::new(vec!["Flight Position", "Ship Name", "Pilot", "Damage"])
         .style(Style::default().cyan().bold())
         .bottom_margin(1);
-    let mut rows = [
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-    ];
-    for (i, scout) in app.scouts.iter().enumerate() {
-        let damage_text = match scout.ship.damage {
-            ShipDamage::Normal => scout.ship.damage.to_string().green(),
-            ShipDamage::Half => scout.ship.damage.to_string().yellow(),
-            ShipDamage::Inoperable => scout.ship.damage.to_string().red(),
-            ShipDamage::Destroyed => scout.ship.damage.to_string().red().underlined(),
-        };
-        let pilot_text = match scout.pilot.rank {
-            Rank::Rookie => scout.pilot.name.clone().white(),
-            Rank::Veteran => format!("|V| {}", scout.pilot.name).blue(),
-            Rank::Ace => format!("|A| {}", scout.pilot.name).green(),
-        };
-        let row = Row::new(vec![
+    let mut rows: Vec<Row> = Vec::new();
+    for scout in app.roster.scouts.iter() {
+        rows.push(Row::new(vec![
             Cell::from(scout.position.to_string()),
             Cell::from(scout.ship.name.clone()),
-            Cell::from(pilot_text),
-            Cell::from(damage_text),
-        ]);
-        rows[i] = row;
+            Cell::from(pilot_span(app.roster.pilot_of(scout.id))),
+            Cell::from(damage_span(&scout.ship.damage)),
+        ]));
     }
     let widths = [
         Constraint::Percentage(25),
@@ -332,18 +858,18 @@ fn draw_main_hangar_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
 
 /// renders the main block for the Crew tab
 fn draw_main_crew_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
-    let header_row = Row::new(vec!["Name", "Kills", "Rank", "Status", "Leaps Injured"])
-        .style(Style::default().cyan().bold())
-        .bottom_margin(1);
-    let mut rows = [
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-        Row::default(),
-    ];
-    for (i, pilot) in app.pilots.iter().enumerate() {
+    let header_row = Row::new(vec![
+        "Name",
+        "Scout",
+        "Kills",
+        "Rank",
+        "Status",
+        "Leaps Injured",
+    ])
+    .style(Style::default().cyan().bold())
+    .bottom_margin(1);
+    let mut rows: Vec<Row> = Vec::new();
+    for pilot in app.roster.pilots.iter() {
         let rank_text = match pilot.rank {
             Rank::Rookie => pilot.rank.to_string().white(),
             Rank::Veteran => pilot.rank.to_string().cyan(),
@@ -354,22 +880,27 @@ fn draw_main_crew_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
             PilotStatus::Injured => pilot.status.to_string().yellow(),
             PilotStatus::Kia => pilot.status.to_string().red(),
         };
+        let scout_text = app
+            .roster
+            .scout_of(pilot.id)
+            .map_or("-".to_string(), |x| x.ship.name.clone());
         // TODO: color leaps injured row?
-        let row = Row::new(vec![
+        rows.push(Row::new(vec![
             Cell::from(pilot.name.clone()),
+            Cell::from(scout_text),
             Cell::from(pilot.kills.to_string()),
             Cell::from(rank_text),
             Cell::from(injured_text),
             Cell::from(pilot.injury_timer.to_string()),
-        ]);
-        rows[i] = row;
+        ]));
     }
     let widths = [
         Constraint::Percentage(20),
         Constraint::Percentage(20),
-        Constraint::Percentage(20),
-        Constraint::Percentage(20),
-        Constraint::Percentage(20),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
+        Constraint::Percentage(15),
     ];
     let table = Table::new(rows, widths)
         .column_spacing(1)
@@ -380,6 +911,174 @@ fn draw_main_crew_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block:
     frame.render_stateful_widget(table, chunk, &mut app.crew_state);
 }
 
+/// renders main block for Map tab
+/// sector map - where we've been, the systems in reach for the next jump and what the sensors
+/// make of each
+fn draw_main_map_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let inner_area = main_block.inner(chunk);
+    main_block.render(chunk, frame.buffer_mut());
+    let sub_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(inner_area);
+
+    let sector = &app.sector;
+    let canvas = Canvas::default()
+        .block(Block::default().title("Sector").borders(Borders::ALL))
+        .marker(symbols::Marker::Braille)
+        .x_bounds([0.0, sector::WIDTH])
+        .y_bounds([0.0, sector::HEIGHT])
+        .paint(|ctx| {
+            for leg in sector.visited.windows(2) {
+                ctx.draw(&CanvasLine {
+                    x1: leg[0].0,
+                    y1: leg[0].1,
+                    x2: leg[1].0,
+                    y2: leg[1].1,
+                    color: Color::DarkGray,
+                });
+            }
+            let (x, y) = sector.position();
+            for (i, choice) in sector.choices.iter().enumerate() {
+                let color = if i == sector.selected {
+                    Color::Yellow
+                } else {
+                    Color::Blue
+                };
+                ctx.draw(&CanvasLine {
+                    x1: x,
+                    y1: y,
+                    x2: choice.x,
+                    y2: choice.y,
+                    color,
+                });
+            }
+            ctx.layer();
+            ctx.print(sector::HOME.0, sector::HOME.1, "H".green().bold());
+            for (i, choice) in sector.choices.iter().enumerate() {
+                let label = (i + 1).to_string();
+                ctx.print(
+                    choice.x,
+                    choice.y,
+                    if i == sector.selected {
+                        label.yellow().bold()
+                    } else {
+                        label.blue()
+                    },
+                );
+            }
+            ctx.print(x, y, "@".cyan().bold());
+        });
+    frame.render_widget(canvas, sub_chunks[0]);
+
+    let detail = forecast_detail(&app.sensors);
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            "Current system: ".cyan(),
+            sector
+                .current
+                .as_ref()
+                .map_or_else(|| "Deep space".to_string(), |x| x.name.clone())
+                .into(),
+        ]),
+        Line::from(""),
+        Line::from("Next jump:").cyan(),
+    ];
+    for (i, choice) in sector.choices.iter().enumerate() {
+        let header = format!(
+            "{} {}. {}",
+            if i == sector.selected { ">>" } else { "  " },
+            i + 1,
+            choice.name
+        );
+        lines.push(if i == sector.selected {
+            header.yellow().bold().into()
+        } else {
+            header.into()
+        });
+        for reading in choice.readings(detail) {
+            lines.push(format!("      {reading}").into());
+        }
+    }
+    if detail < 3 {
+        lines.push(Line::from(""));
+        lines.push("Damaged sensors - readings are partial.".yellow().into());
+    }
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(
+            Block::default()
+                .title("Sensor Readings")
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, sub_chunks[1]);
+}
+
+/// renders main block for Outpost tab
+/// the outpost in the current system, what it has in stock and what it charges
+fn draw_main_outpost_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_block: Block) {
+    let inner_area = main_block.inner(chunk);
+    main_block.render(chunk, frame.buffer_mut());
+    let system = app.sector.current.as_ref();
+    let Some(outpost) = system.and_then(|x| x.outpost.as_ref()) else {
+        let paragraph =
+            Paragraph::new("No outpost in this system.  Watch the Map tab for beacons.")
+                .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner_area);
+        return;
+    };
+    let sub_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(inner_area);
+
+    let stock_text = Text::from(vec![
+        Line::from(vec![
+            "Outpost at ".cyan(),
+            system.map_or_else(String::new, |x| x.name.clone()).cyan(),
+        ]),
+        Line::from(""),
+        Line::from(format!(
+            "Fuel: {} in stock, {} parts each",
+            outpost.fuel_stock, outpost.fuel_price
+        )),
+        Line::from(format!(
+            "Veteran pilots: {} for hire, {} parts each",
+            outpost.pilots, outpost.pilot_price
+        )),
+        Line::from(format!(
+            "Dock crews: {} job(s) open, {} parts an overhaul",
+            outpost.repairs, outpost.repair_price
+        )),
+        Line::from(""),
+        Line::from(vec!["Parts: ".into(), get_parts_string(app.parts.total())]),
+        Line::from(vec!["Fuel: ".into(), get_fuel_string(&app.fuel)]),
+        Line::from(""),
+        Line::from(app.game_text.as_str()),
+    ]);
+    frame.render_widget(
+        Paragraph::new(stock_text).wrap(Wrap { trim: true }),
+        sub_chunks[0],
+    );
+
+    let items: Vec<Line> = offers(app)
+        .iter()
+        .map(|(trade, price)| {
+            let line = Line::from(format!("{trade} - {price} parts"));
+            if *price > app.parts.total() {
+                line.dark_gray()
+            } else {
+                line
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().title(" Trades ").borders(Borders::ALL))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">>");
+    frame.render_stateful_widget(list, sub_chunks[1], &mut app.outpost_state);
+}
+
 /// renders main block for About tab
 fn draw_main_about_tab(frame: &mut Frame, chunk: Rect, main_block: Block) {
     let paragraph = Paragraph::new(ABOUT_STR)
@@ -401,54 +1100,7 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
     let inner_area = main_block.inner(chunk);
     main_block.render(chunk, frame.buffer_mut());
     if app.in_combat && app.combat.is_some() {
-        let mut combat = app.combat.clone().unwrap();
-
-        // check if combat is resolved
-        if combat.enemy_stats.iter().all(|x| x.hp == 0 || x.fuel == 0) {
-            app.combat = None; // TODO: this breaks the "search the wreckage step"
-            app.in_combat = false;
-            // app.jump_step = JumpStep::Step4; // TODO: delete?
-        }
-
-        // reset pilot information in case order changed
-        // TODO: maybe don't want to do this during a combat phase?
-        for i in 0..app.scouts.len() {
-            app.scouts[i].pilot = app.pilots[i].clone();
-        }
-
-        if combat.rounds == 1 {
-            combat.laser_fired = true;
-        }
-
-        // TODO: should this be mode dependent?
-        // check to see if all of scouts have taken a turn
-        if combat.scout_turns.iter().all(|x| *x) && combat.laser_fired {
-            combat.scout_half = false; // now enemy turn
-            combat.scout_turns = vec![false; combat.scout_formation.len()]; // reset
-        }
-        if combat.enemy_turns.iter().all(|x| *x) {
-            combat.scout_half = true;
-            combat.enemy_turns = vec![false; combat.enemy_formation.len()];
-            combat.laser_fired = false;
-            // end of round, +/- fuel, round counter, etc.
-            combat.rounds += 1;
-            app.current_leap.combat_rounds += 1; // update log
-            for (i, enemy) in app.combat.as_ref().unwrap().enemy_stats.iter().enumerate() {
-                if enemy.fuel > 0 {
-                    combat.enemy_stats[i].fuel -= 1;
-                }
-            }
-        }
-
-        // skip turns for Scouts that are inoperable, destroyed, or KIA
-        for (i, scout) in combat.scout_formation.iter().enumerate() {
-            if scout.ship.damage == ShipDamage::Inoperable
-                || scout.ship.damage == ShipDamage::Destroyed
-                || scout.pilot.status == PilotStatus::Kia
-            {
-                combat.scout_turns[i] = true;
-            }
-        }
+        let combat = app.combat.clone().unwrap();
 
         let sub_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -456,6 +1108,7 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
                 Constraint::Length(3),
                 Constraint::Min(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .split(inner_area);
         let ship_chunks = Layout::default()
@@ -468,13 +1121,27 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
         } else {
             "Colony ship not yet in range."
         };
+        let recall_text = match recall_cost(&app.scout_bay) {
+            Ok(cost) => format!("Recall costs {cost} part(s)"),
+            Err(_) => "Scout bay can't recall".to_string(),
+        };
+        let jump_text = match emergency_jump_cost(&app.engine) {
+            Ok(extra) => format!("Jump costs {} fuel", extra + 1),
+            Err(_) => "Engines can't jump".to_string(),
+        };
+        let boss_text = match combat.boss_phase() {
+            Some(phase) => format!(" | Capital ship phase {phase}/3"),
+            None => String::new(),
+        };
         let paragraph = Paragraph::new(format!(
-            "Round: {} | {} | out of fuel after X rounds: Mk1 - 3, Mk2 - 4, Mk3 - 5",
-            combat.rounds, colony_ship_text
+            "Round: {} | {} | {} | {}{}",
+            combat.rounds, colony_ship_text, recall_text, jump_text, boss_text
         ));
         frame.render_widget(paragraph, sub_chunks[0]);
         let combat_paragraph = Paragraph::new(combat.combat_text.clone());
-        frame.render_widget(combat_paragraph, sub_chunks[2]);
+        frame.render_widget(combat_paragraph, sub_chunks[3]);
+        let preview = attack_preview(app, &combat);
+        frame.render_widget(Paragraph::new(preview), sub_chunks[2]);
 
         let ship_border = if app.combat_select {
             Borders::ALL
@@ -488,30 +1155,37 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
         };
 
         let mut rows: Vec<Row> = Vec::new();
-        for scout in &combat.scout_formation {
-            let damage_text = match scout.ship.damage {
-                ShipDamage::Normal => scout.ship.damage.to_string().green(),
-                ShipDamage::Half => scout.ship.damage.to_string().yellow(),
-                ShipDamage::Inoperable => scout.ship.damage.to_string().red(),
-                ShipDamage::Destroyed => scout.ship.damage.to_string().red().underlined(),
+        for scout_id in &combat.scout_formation {
+            let Some(scout) = app.roster.scout(*scout_id) else {
+                continue;
             };
-            let pilot_text = match scout.pilot.rank {
-                Rank::Rookie => scout.pilot.name.clone().white(),
-                Rank::Veteran => format!("|V| {}", scout.pilot.name).blue(),
-                Rank::Ace => format!("|A| {}", scout.pilot.name).green(),
+            let pilot = app.roster.pilot_of(*scout_id);
+            let recalled = combat.recalled.contains(scout_id);
+            let health_text = match pilot.map(|x| &x.status) {
+                Some(PilotStatus::Normal) => "Normal".green(),
+                Some(PilotStatus::Injured) => "Injured".yellow(),
+                Some(PilotStatus::Kia) => "KIA".red(),
+                None => "-".white(),
             };
-            let health_text = match scout.pilot.status {
-                PilotStatus::Normal => "Normal".green(),
-                PilotStatus::Injured => "Injured".yellow(),
-                PilotStatus::Kia => "KIA".red(),
+            // recalled scouts are back in the bay, their slot is open
+            let (position, style) = if recalled {
+                (
+                    "Recalled".to_string(),
+                    Style::default().dark_gray().italic(),
+                )
+            } else {
+                (scout.position.to_string(), Style::default())
             };
-            rows.push(Row::new(vec![
-                Cell::from(scout.position.to_string()),
-                Cell::from(scout.ship.name.clone()),
-                Cell::from(pilot_text),
-                Cell::from(health_text),
-                Cell::from(damage_text),
-            ]));
+            rows.push(
+                Row::new(vec![
+                    Cell::from(position),
+                    Cell::from(scout.ship.name.clone()),
+                    Cell::from(pilot_span(pilot)),
+                    Cell::from(health_text),
+                    Cell::from(damage_span(&scout.ship.damage)),
+                ])
+                .style(style),
+            );
         }
         let header_row = Row::new(vec![
             "Flight Position",
@@ -538,22 +1212,48 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
         frame.render_stateful_widget(scout_table, ship_chunks[0], &mut app.combat_scout_state);
 
         let mut rows: Vec<Row> = Vec::new();
-        for fighter in &combat.enemy_stats {
-            rows.push(Row::new(vec![
-                Cell::from(fighter.model.to_string()),
-                Cell::from(fighter.guns.to_string()),
-                Cell::from(fighter.fuel.to_string()),
-                Cell::from(fighter.hp.to_string()),
-            ]));
+        for (i, fighter) in combat.enemy_stats.iter().enumerate() {
+            // countdown to when the fighter runs dry and breaks off
+            let (state, style) = match fighter.state {
+                FighterState::Active if combat.shielded(i) => {
+                    ("Shielded".to_string(), Style::default().blue())
+                }
+                FighterState::Active if fighter.model.is_capital() => {
+                    ("Holding on".to_string(), Style::default().magenta())
+                }
+                FighterState::Active => (
+                    format!(
+                        "Leaves after rd {}",
+                        fighter.breaks_off_after(combat.rounds)
+                    ),
+                    Style::default(),
+                ),
+                FighterState::Destroyed => (fighter.state.to_string(), Style::default().red()),
+                FighterState::Retreated => (
+                    fighter.state.to_string(),
+                    Style::default().dark_gray().italic(),
+                ),
+            };
+            rows.push(
+                Row::new(vec![
+                    Cell::from(fighter.model.to_string()),
+                    Cell::from(fighter.guns.to_string()),
+                    Cell::from(fighter.fuel_text()),
+                    Cell::from(fighter.hp.to_string()),
+                    Cell::from(state),
+                ])
+                .style(style),
+            );
         }
-        let header_row = Row::new(vec!["Type", "Guns", "Fuel", "HP"])
+        let header_row = Row::new(vec!["Type", "Guns", "Fuel", "HP", "Status"])
             .style(Style::default().cyan().bold())
             .bottom_margin(1);
         let widths = [
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(15),
+            Constraint::Percentage(40),
         ];
         let enemy_table = Table::new(rows, widths)
             .column_spacing(1)
@@ -562,9 +1262,6 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
             .highlight_style(Style::default().reversed())
             .highlight_symbol(">>");
         frame.render_stateful_widget(enemy_table, ship_chunks[1], &mut app.combat_enemy_state);
-
-        combat_to_app(&combat, app);
-        app.combat = Some(combat);
     } else {
         // TODO: somehow wipe combat tab after it's resolved?
         let paragraph = Paragraph::new("Not in combat at the moment - whew!");
@@ -572,6 +1269,81 @@ fn draw_main_combat_tab(app: &mut App, frame: &mut Frame, chunk: Rect, main_bloc
     }
 }
 
+/// odds for the selected scout attacking the selected fighter, plus mining laser expectations
+fn attack_preview<'a>(app: &App, combat: &Combat) -> Text<'a> {
+    let laser_line = Line::from(vec![
+        "Mining laser expected damage: ".into(),
+        format!("{:.2}", laser_expected_damage(app.mining_laser.upgrade)).into(),
+        format!(" (tier {})", app.mining_laser.upgrade).into(),
+        if app.mining_laser.upgrade < MAX_TIER {
+            format!(
+                " / {:.2} at tier {}",
+                laser_expected_damage(app.mining_laser.upgrade + 1),
+                app.mining_laser.upgrade + 1
+            )
+            .white()
+        } else {
+            "".into()
+        },
+        if combat.laser_jammed() {
+            " JAMMED".red().bold()
+        } else {
+            "".into()
+        },
+    ]);
+    let (Some(scout_pos), Some(enemy_pos)) = (
+        app.combat_scout_state.selected(),
+        app.combat_enemy_state.selected(),
+    ) else {
+        return Text::from(vec![
+            "Select a scout and a target to see attack odds.".into(),
+            laser_line,
+        ]);
+    };
+    let scout_id = combat.scout_formation.get(scout_pos).copied();
+    let (Some(scout), Some(pilot), Some(enemy)) = (
+        scout_id.and_then(|x| app.roster.scout(x)),
+        scout_id.and_then(|x| app.roster.pilot_of(x)),
+        combat.enemy_stats.get(enemy_pos),
+    ) else {
+        return Text::from(vec![laser_line]);
+    };
+    let odds = attack_odds(&scout.ship, pilot);
+    let attack_line = Line::from(vec![
+        format!("{} vs {}: ", pilot.name, enemy.model).cyan(),
+        format!("0 dmg {} ", percent(odds[0])).into(),
+        format!("1 dmg {} ", percent(odds[1])).yellow(),
+        format!("2 dmg {} ", percent(odds[2])).green(),
+        "| kill chance ".into(),
+        percent(kill_chance(&scout.ship, pilot, enemy.hp))
+            .red()
+            .bold(),
+    ]);
+    Text::from(vec![attack_line, laser_line])
+}
+
+/// pilot name colored by rank, with a |V|/|A| tag for veterans and aces
+fn pilot_span(pilot: Option<&Pilot>) -> Span<'static> {
+    match pilot {
+        Some(pilot) => match pilot.rank {
+            Rank::Rookie => pilot.name.clone().white(),
+            Rank::Veteran => format!("|V| {}", pilot.name).blue(),
+            Rank::Ace => format!("|A| {}", pilot.name).green(),
+        },
+        None => "No pilot".white(),
+    }
+}
+
+/// scout damage colored green -> yellow -> red
+fn damage_span(damage: &ShipDamage) -> Span<'static> {
+    match damage {
+        ShipDamage::Normal => damage.to_string().green(),
+        ShipDamage::Half => damage.to_string().yellow(),
+        ShipDamage::Inoperable => damage.to_string().red(),
+        ShipDamage::Destroyed => damage.to_string().red().underlined(),
+    }
+}
+
 /// convenience function for incrementing table selection (down arrow)
 pub fn select_down(current: Option<usize>, length: usize) -> Option<usize> {
     if length < 1 {
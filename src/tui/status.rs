@@ -1,11 +1,14 @@
 use ratatui::prelude::Span;
 use ratatui::style::Stylize;
 
-use crate::gamerules::ship::{Status, SubSystem};
+use crate::gamerules::{
+    ship::{max_hull, Status, SubSystem},
+    threat::Fighter,
+};
 
 /// returns colored string of fuel amount
 /// green -> yellow -> red as fuel gets lower
-pub fn get_fuel_string(fuel: &u64) -> Span {
+pub fn get_fuel_string(fuel: &u64) -> Span<'_> {
     if *fuel > 4 {
         fuel.to_string().green()
     } else if *fuel <= 4 && *fuel > 2 {
@@ -17,10 +20,10 @@ pub fn get_fuel_string(fuel: &u64) -> Span {
 
 /// returns colored string of parts amount
 /// green -> yellow -> red as fuel gets lower
-pub fn get_parts_string(parts: &u64) -> Span {
-    if *parts >= 6 {
+pub fn get_parts_string(parts: u64) -> Span<'static> {
+    if parts >= 6 {
         parts.to_string().green()
-    } else if *parts < 6 && *parts >= 3 {
+    } else if (3..6).contains(&parts) {
         parts.to_string().yellow()
     } else {
         parts.to_string().red()
@@ -29,8 +32,8 @@ pub fn get_parts_string(parts: &u64) -> Span {
 
 /// returns colored string of hull damage
 /// green -> yellow -> red as damge increases
-pub fn get_hull_string(damage: u64, upgraded: bool) -> Span<'static> {
-    let max: u64 = if upgraded { 7 } else { 6 };
+pub fn get_hull_string(damage: u64, tier: u8) -> Span<'static> {
+    let max = max_hull(tier);
     if damage > 4 {
         format!("{} / {}", damage, max).red()
     } else if damage <= 4 && damage > 2 {
@@ -49,3 +52,17 @@ pub fn get_subsys_string(subsystem: &SubSystem) -> Span<'static> {
         Status::Inoperable => "Inoperable".red(),
     }
 }
+
+/// returns colored list of enemies following the colony ship and the hp they have left
+/// green when nobody is following
+pub fn get_pursuers_string(pursuers: &[Fighter]) -> Span<'static> {
+    if pursuers.is_empty() {
+        return "None".green();
+    }
+    pursuers
+        .iter()
+        .map(|x| format!("{} ({} hp)", x.model, x.hp))
+        .collect::<Vec<String>>()
+        .join(", ")
+        .red()
+}